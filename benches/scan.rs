@@ -0,0 +1,137 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use stringer::config::{Encoding, StringerConfig};
+use stringer::table::{ByteTable, PRINT, SKIP};
+
+/// Size of the generated input
+const SIZE: usize = 8 * 1024 * 1024;
+
+/// generates binary looking data with runs of up to `run` words of
+/// text in between, the same data is generated on every run
+fn data(utf8: bool, run: u64) -> Vec<u8> {
+    let words: &[&str] = match utf8 {
+        true => &["alpha", "Größe", "беta", "naïve", "日本語", "config"],
+        false => &["alpha", "beta", "gamma", "delta", "printf", "config"],
+    };
+
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    let mut data = Vec::with_capacity(SIZE);
+    while data.len() < SIZE {
+        let r = next();
+        match r % 4 {
+            0 => {
+                for _ in 0..(r >> 8) % run + 1 {
+                    data.extend_from_slice(words[(next() % words.len() as u64) as usize].as_bytes());
+                    data.push(b'_');
+                }
+            }
+            _ => data.extend((0..(r >> 8) % 48).map(|_| next() as u8)),
+        }
+    }
+    data.truncate(SIZE);
+    data
+}
+
+/// The checks of `should_read` for an ASCII byte, the way
+/// the scanner classified every byte before the lookup table
+fn should_read(config: &StringerConfig, b: u8) -> bool {
+    let c = b as char;
+    if !c.is_ascii() {
+        return false;
+    }
+    if c.is_ascii_alphanumeric() {
+        return true;
+    }
+    if config.special && c.is_ascii_punctuation() {
+        return true;
+    }
+    if config.whitespace_include && (c == ' ' || c == '\t' || c == '\x0b') {
+        return true;
+    }
+    if config.line_include && (c == '\n' || c == '\r') {
+        return true;
+    }
+    false
+}
+
+/// Counts the runs of printable bytes, measuring each run with `span`
+/// as the scanner does. The class of a byte is found with `class`.
+fn runs<C, S>(data: &[u8], class: C, span: S) -> usize
+where
+    C: Fn(u8) -> u8,
+    S: Fn(&[u8], u8) -> usize,
+{
+    let mut runs = 0;
+    let mut i = 0;
+    while i < data.len() {
+        let c = class(data[i]);
+        runs += (c == PRINT) as usize;
+        i += span(&data[i..], c);
+    }
+    runs
+}
+
+fn span(c: &mut Criterion) {
+    // short runs as in binaries and long ones as in text
+    for (name, run) in [("span", 6), ("span_text", 60)] {
+        span_of(c, name, data(false, run));
+    }
+}
+
+fn span_of(c: &mut Criterion, name: &str, input: Vec<u8>) {
+    let config = StringerConfig::default();
+    let check = |b| match should_read(&config, b) {
+        true => PRINT,
+        false => SKIP,
+    };
+    let table = ByteTable::from_fn(check);
+    let checks = |d: &[u8], c| d.iter().take_while(|&&b| check(b) == c).count();
+    let lookups = |d: &[u8], c| table.span(d, c);
+    let expected = runs(&input, check, checks);
+    assert_eq!(runs(&input, |b| table.class(b), lookups), expected);
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.sample_size(20);
+    group.bench_function("should_read", |b| b.iter(|| runs(&input, check, checks)));
+    group.bench_function("table", |b| b.iter(|| runs(&input, |b| table.class(b), lookups)));
+    group.finish();
+}
+
+fn scan(c: &mut Criterion) {
+    for (name, encoding) in [("ascii", Encoding::Ascii), ("utf8", Encoding::Utf8)] {
+        let input = data(encoding == Encoding::Utf8, 6);
+        let mut config = StringerConfig::default();
+        config.set_encoding(encoding);
+
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.sample_size(20);
+        // the scanner before the byte table checked every character on its own
+        let mut legacy = config.clone();
+        legacy.set_table_scan(false);
+        group.bench_function("legacy", |b| {
+            b.iter(|| stringer::Slices::new(&input, legacy.clone()).count())
+        });
+        group.bench_function("stringer", |b| {
+            b.iter(|| stringer::Slices::new(&input, config.clone()).count())
+        });
+        group.bench_function("stringer_stream", |b| {
+            b.iter(|| {
+                let mut s = stringer::Stringer::from_reader(&input[..]);
+                s.set_config(config.clone());
+                s.count()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, span, scan);
+criterion_main!(benches);
@@ -0,0 +1,251 @@
+use std::io::Read;
+
+use crate::error::StringerError;
+
+/// Number of bytes needed to detect every archive format and to check
+/// its first header, the magic number of tar archives is inside of it
+pub const PROBE_LEN: usize = 512;
+/// Separates the path of an archive from the path of a member in
+/// the source of the results, as in `app.apk!classes.dex`
+pub const SEPARATOR: char = '!';
+
+/// Archive formats whose members are scanned one by one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Archive {
+    /// zip archives and the formats built on them such as JAR and APK
+    Zip,
+    /// POSIX and GNU tar archives
+    Tar,
+    /// Unix ar archives such as static libraries and Debian packages
+    Ar,
+}
+
+/// Called with the path and the contents of every file in an archive
+pub type Member<'f> = dyn FnMut(&str, &mut dyn Read) -> Result<(), StringerError> + 'f;
+
+impl Archive {
+    /// detects the format of an archive from its first bytes
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        // an empty zip archive is only its end of central directory
+        if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+            Some(Archive::Zip)
+        } else if head.starts_with(b"!<arch>\n") {
+            Some(Archive::Ar)
+        } else if head.get(257..262) == Some(b"ustar") {
+            Some(Archive::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// Checks if the first bytes of the input are really the start of an
+    /// archive, which only has a magic number like any file may have.
+    /// A zip archive needs its whole bytes for its central directory,
+    /// `whole` is set when `head` is the whole input.
+    pub fn probe(&self, head: &[u8], whole: bool) -> bool {
+        match self {
+            Archive::Zip => zip::ZipArchive::new(std::io::Cursor::new(head)).is_ok(),
+            Archive::Tar => head.len() >= PROBE_LEN && checksum(&head[..PROBE_LEN]),
+            // an empty ar archive is only its magic number
+            Archive::Ar => head.get(66..68) == Some(b"`\n") || (whole && head.len() == 8),
+        }
+    }
+}
+
+/// checks the checksum of a tar header, the sum of its bytes
+/// where the checksum field itself is counted as spaces
+fn checksum(header: &[u8]) -> bool {
+    let field = &header[148..156];
+    let digits = String::from_utf8_lossy(field);
+    let Ok(sum) = u32::from_str_radix(digits.trim_matches(|c| c == '\0' || c == ' '), 8) else {
+        return false;
+    };
+    let bytes: u32 = header.iter().map(|&b| b as u32).sum();
+    let spaces = field.iter().map(|&b| b as u32).sum::<u32>();
+    bytes - spaces + 8 * b' ' as u32 == sum
+}
+
+/// Passes the files of a zip archive to `f` in the order of its central
+/// directory, which is at the end of the archive so the whole of it is
+/// needed. Files that cannot be decoded, such as encrypted ones, are skipped.
+pub fn zip(data: &[u8], f: &mut Member) -> Result<(), StringerError> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(std::io::Error::from)?;
+    for i in 0..archive.len() {
+        let mut file = match archive.by_index(i) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::UnsupportedArchive(_)) |
+            Err(zip::result::ZipError::CompressionMethodNotSupported(_)) => continue,
+            Err(e) => return Err(std::io::Error::from(e).into()),
+        };
+        if !file.is_file() {
+            continue;
+        }
+        let name = file.name().to_string();
+        f(&name, &mut file)?;
+    }
+    Ok(())
+}
+
+/// Passes the regular files of a tar archive to `f` while it is streamed,
+/// links and directories have no contents of their own.
+pub fn tar<R: Read>(r: R, f: &mut Member) -> Result<(), StringerError> {
+    let mut archive = tar::Archive::new(r);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        f(&name, &mut entry)?;
+    }
+    Ok(())
+}
+
+/// Passes the members of an ar archive to `f` while it is streamed,
+/// the symbol and name tables are not members.
+pub fn ar<R: Read>(r: R, f: &mut Member) -> Result<(), StringerError> {
+    let mut archive = ar::Archive::new(r);
+    while let Some(entry) = archive.next_entry() {
+        let mut entry = entry?;
+        let name = String::from_utf8_lossy(entry.header().identifier()).into_owned();
+        f(&name, &mut entry)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StringerConfig;
+    use crate::Stringer;
+    use std::io::Write;
+
+    fn tar_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut b = tar::Builder::new(Vec::new());
+        for (path, data) in files {
+            let mut h = tar::Header::new_gnu();
+            h.set_size(data.len() as u64);
+            h.set_mode(0o644);
+            h.set_cksum();
+            b.append_data(&mut h, path, *data).unwrap();
+        }
+        b.into_inner().unwrap()
+    }
+
+    fn ar_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut b = ar::Builder::new(Vec::new());
+        for (id, data) in files {
+            b.append(&ar::Header::new(id.as_bytes().to_vec(), data.len() as u64), *data).unwrap();
+        }
+        b.into_inner().unwrap()
+    }
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut w = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (path, data) in files {
+            w.start_file(*path, zip::write::SimpleFileOptions::default()).unwrap();
+            w.write_all(data).unwrap();
+        }
+        w.finish().unwrap().into_inner()
+    }
+
+    fn gzip_of(data: &[u8]) -> Vec<u8> {
+        let mut e = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        e.write_all(data).unwrap();
+        e.finish().unwrap()
+    }
+
+    /// scans an input like the command line does, every member of the
+    /// archives as an input of its own
+    fn scan(s: Stringer<'_>, name: &str, found: &mut Vec<(String, String, usize)>) -> Result<(), StringerError> {
+        let mut s = s.decompressed()?;
+        if let Some(kind) = s.archive()? {
+            s.set_source(name.to_string());
+            return s.read_members(kind, |source, m| scan(m, source, found));
+        }
+        for r in s {
+            let source = r.source().unwrap_or(name).to_string();
+            found.push((source, r.as_str().to_string(), r.compression().len()));
+        }
+        Ok(())
+    }
+
+    fn strings(data: &[u8], depth: u64) -> Vec<(String, String, usize)> {
+        let mut config = StringerConfig::default();
+        config.set_archive_depth(depth);
+        let mut found = Vec::new();
+        let mut s = Stringer::from_slice(data);
+        s.set_config(config.clone());
+        scan(s, "in", &mut found).unwrap();
+        // a stream is read the same way as a slice
+        let mut streamed = Vec::new();
+        let mut s = Stringer::from_reader(data);
+        s.set_config(config);
+        scan(s, "in", &mut streamed).unwrap();
+        assert_eq!(found, streamed);
+        found
+    }
+
+    fn found(source: &str, string: &str, compressions: usize) -> (String, String, usize) {
+        (source.to_string(), string.to_string(), compressions)
+    }
+
+    #[test]
+    fn members() {
+        let files: [(&str, &[u8]); 2] = [("dir/alpha.txt", b"\x01alpha\x02"), ("beta.txt", b"\x00beta string")];
+        for (data, kind) in [(zip_of(&files), Archive::Zip), (tar_of(&files), Archive::Tar), (ar_of(&files[1..]), Archive::Ar)] {
+            assert_eq!(Archive::detect(&data), Some(kind));
+            let expected: Vec<_> = match kind {
+                Archive::Ar => vec![found("in!beta.txt", "beta string", 0)],
+                _ => vec![found("in!dir/alpha.txt", "alpha", 0), found("in!beta.txt", "beta string", 0)],
+            };
+            assert_eq!(strings(&data, 1), expected, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn nested_archives() {
+        let inner = gzip_of(&tar_of(&[("deep.txt", b"deep inside")]));
+        let data = zip_of(&[("inner.tgz", &inner), ("top.txt", b"on top")]);
+        assert_eq!(strings(&data, 2), vec![
+            found("in!inner.tgz!deep.txt", "deep inside", 1),
+            found("in!top.txt", "on top", 0),
+        ]);
+
+        // archives deeper than the depth are scanned as they are
+        let shallow = strings(&data, 1);
+        assert!(shallow.iter().all(|(source, _, _)| source.starts_with("in!")));
+        assert!(shallow.contains(&found("in!inner.tgz", "deep.txt", 1)));
+        assert!(shallow.contains(&found("in!inner.tgz", "deep inside", 1)));
+        assert!(shallow.contains(&found("in!top.txt", "on top", 0)));
+
+        let flat = strings(&data, 0);
+        assert!(flat.iter().all(|(source, _, _)| source == "in"));
+        assert!(flat.iter().any(|(_, s, _)| s.contains("top.txt")));
+    }
+
+    #[test]
+    fn fake_magic() {
+        let zip = zip_of(&[("alpha.txt", b"alpha string")]);
+        let tar = tar_of(&[("alpha.txt", b"alpha string")]);
+        let mut bad_sum = tar.clone();
+        bad_sum[0] = b'b';
+        let inputs = [
+            b"PK\x03\x04 plain text after a zip magic".to_vec(),
+            zip[..zip.len() - 10].to_vec(),
+            b"!<arch>\nplain text after an ar magic".to_vec(),
+            bad_sum,
+        ];
+        for data in inputs {
+            let kind = Archive::detect(&data).unwrap();
+            assert!(!kind.probe(&data, true), "{:?}", kind);
+            // the raw bytes are scanned instead
+            let found = strings(&data, 1);
+            assert!(!found.is_empty(), "{:?}", kind);
+            assert!(found.iter().all(|(source, _, _)| source == "in"));
+        }
+        assert!(Archive::Ar.probe(b"!<arch>\n", true));
+        assert!(!Archive::Ar.probe(b"!<arch>\n", false));
+        assert!(Archive::Tar.probe(&tar, true));
+    }
+}
@@ -13,15 +13,69 @@ pub struct Args {
     pub output_format: Option<String>,
     /// length option to be included in ouput
     pub length: Option<bool>,
+    /// entropy option to be included in output
+    pub entropy: Option<bool>,
+    /// lowest entropy of the extracted strings
+    pub entropy_min: Option<f64>,
+    /// highest entropy of the extracted strings
+    pub entropy_max: Option<f64>,
+    /// tags the strings with the kinds of values in them
+    pub classify: Option<bool>,
+    /// kinds of values the extracted strings must hold
+    pub kinds: Option<Vec<String>>,
+    /// checks the strings against the bundled secret rules
+    pub secrets: Option<bool>,
+    /// files of secret rules the strings are checked against
+    pub rules: Option<Vec<String>>,
+    /// only extracts the strings flagged by the secret rules
+    pub findings_only: Option<bool>,
+    /// fails once strings are flagged by the secret rules
+    pub fail_on_findings: Option<bool>,
     /// line feed and CR
     pub line_include: Option<bool>,
-    /// regex option
-    pub regex: Option<String>,
+    /// regex patterns option
+    pub regex: Option<Vec<String>>,
+    /// file of regex patterns, one per line
+    pub regex_file: Option<String>,
+    /// regex patterns of the strings that are dropped
+    pub exclude_regex: Option<Vec<String>>,
+    /// file of regex patterns of the strings that are dropped, one per line
+    pub exclude_regex_file: Option<String>,
+    /// regex patterns matched against the raw bytes of the input
+    pub byte_regex: Option<Vec<String>>,
+    /// file of regex patterns matched against the raw bytes, one per line
+    pub byte_regex_file: Option<String>,
+    /// extracts the matches or the named groups of the regex patterns
+    pub extract: Option<Vec<String>>,
     /// output file
     pub output: Option<String>,
-    /// input file or . for directory
-    pub input: Option<String>,
+    /// input files, directories or - for the standard input
+    pub input: Option<Vec<String>>,
+    /// globs of the files scanned in a directory
+    pub include: Option<Vec<String>>,
+    /// globs of the files and directories skipped in a directory
+    pub exclude: Option<Vec<String>>,
+    /// follows symbolic links in a directory
+    pub follow_symlinks: Option<bool>,
     pub split: Option<u64>,
+    /// encoding of the strings
+    pub encoding: Option<String>,
+    /// unicode categories treated as printable
+    pub categories: Option<Vec<String>>,
+    /// radix of the printed offsets
+    pub radix: Option<String>,
+    /// number of scanning threads
+    pub threads: Option<u64>,
+    /// scans only the data sections of object files
+    pub data: Option<bool>,
+    /// names of the sections of object files that are scanned
+    pub sections: Option<Vec<String>>,
+    /// architecture of the slices of universal binaries that are scanned
+    pub arch: Option<String>,
+    /// decompresses compressed inputs
+    pub decompress: Option<bool>,
+    /// number of nested archives whose members are scanned
+    pub archive_depth: Option<u64>,
 }
 
 impl Args {
@@ -50,8 +104,12 @@ impl Args {
                 clap::Arg::new("special")
                     .short('s')
                     .long("special")
-                    .help("allows special characters to be included")
+                    .help("allows special characters to be included, by default only with --secrets or --rules since the secrets span them")
                     .default_value("false")
+                    .default_value_ifs([
+                        ("secrets", clap::builder::ArgPredicate::from("true"), Some("true")),
+                        ("rules", clap::builder::ArgPredicate::IsPresent, Some("true")),
+                    ])
                     .required(false)
                     .value_parser(clap::value_parser!(bool)),
             )
@@ -67,8 +125,12 @@ impl Args {
                 clap::Arg::new("whitespace")
                     .short('w')
                     .long("whitespace")
-                    .help("allows whitespaces to be included")
+                    .help("allows whitespaces to be included, by default only with --secrets or --rules since the secrets span them")
                     .default_value("false")
+                    .default_value_ifs([
+                        ("secrets", clap::builder::ArgPredicate::from("true"), Some("true")),
+                        ("rules", clap::builder::ArgPredicate::IsPresent, Some("true")),
+                    ])
                     .required(false)
                     .value_parser(clap::value_parser!(bool)),
             )
@@ -76,7 +138,7 @@ impl Args {
                 clap::Arg::new("format")
                     .short('f')
                     .long("format")
-                    .help("output format of the strings. JSON, XML, YAML, LITERAL")
+                    .help("output format of the strings. JSON, XML, YAML, CSV, TSV, LITERAL")
                     .default_value("literal")
                     .required(false)
                     .value_parser(clap::value_parser!(String)),
@@ -90,6 +152,76 @@ impl Args {
                     .required(false)
                     .value_parser(clap::value_parser!(bool)),
             )
+            .arg(
+                clap::Arg::new("entropy")
+                    .long("entropy")
+                    .help("write the Shannon entropy of the string in bits per character on the output")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("min-entropy")
+                    .long("min-entropy")
+                    .help("drop the strings with a lower entropy in bits per character, such as AAAAAAAA at 0")
+                    .required(false)
+                    .value_parser(clap::value_parser!(f64)),
+            )
+            .arg(
+                clap::Arg::new("max-entropy")
+                    .long("max-entropy")
+                    .help("drop the strings with a higher entropy in bits per character")
+                    .required(false)
+                    .value_parser(clap::value_parser!(f64)),
+            )
+            .arg(
+                clap::Arg::new("classify")
+                    .long("classify")
+                    .help("tag the strings with the kinds of values found in them, such as url or ipv4")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("kinds")
+                    .long("kinds")
+                    .help("only extract the strings holding a value of the kinds, tagged with them. url, ipv4, ipv6, email, domain, windows-path, unix-path, registry-key, guid, hash")
+                    .required(false)
+                    .value_delimiter(',')
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("secrets")
+                    .long("secrets")
+                    .help("flag the strings holding secrets with the bundled rules, such as cloud keys, tokens and private keys. Turns on --special and --whitespace unless they are given")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("rules")
+                    .long("rules")
+                    .help("flag the strings with the secret rules of a TOML or JSON file, can be repeated. Turns on --special and --whitespace unless they are given")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("findings-only")
+                    .long("findings-only")
+                    .help("only extract the strings flagged by the secret rules")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("fail-on-findings")
+                    .long("fail-on-findings")
+                    .help("exit with code 7 when strings are flagged by the secret rules")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
             .arg(
                 clap::Arg::new("line")
                 .short('L')
@@ -110,15 +242,162 @@ impl Args {
                 clap::Arg::new("in")
                     .short('i')
                     .long("in")
-                    .help("input file to extract")
-                    .required(true)
+                    .help("input files to extract, directories are scanned recursively and - is the standard input")
+                    .required(false)
+                    .num_args(1..)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("include")
+                    .long("include")
+                    .help("only scan files in a directory matching the glob, can be repeated")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
                     .value_parser(clap::value_parser!(String)),
             )
+            .arg(
+                clap::Arg::new("exclude")
+                    .long("exclude")
+                    .help("skip files and directories matching the glob, can be repeated")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("follow")
+                    .long("follow-symlinks")
+                    .help("follow symbolic links while scanning a directory")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
             .arg(
                 clap::Arg::new("regex")
                     .short('r')
                     .long("regex")
-                    .help("pattern for searching specific strings")
+                    .help("pattern for searching specific strings, can be repeated to keep the strings matching any of them")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("exclude-regex")
+                    .short('v')
+                    .long("exclude-regex")
+                    .help("drop the strings matching the pattern, such as compiler banners, even when they match --regex. Can be repeated")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("exclude-regex-file")
+                    .long("exclude-regex-file")
+                    .help("file of patterns of the strings that are dropped, one per line. Empty lines and lines starting with # are skipped")
+                    .required(false)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("byte-regex")
+                    .long("byte-regex")
+                    .help("report the matches of the pattern on the raw bytes of the input instead of the strings, unprintable bytes are written as \\xNN and the encoding is raw. Streams are matched a chunk at a time, so matches longer than 64 KiB may be cut. Can be repeated")
+                    .required(false)
+                    .action(clap::ArgAction::Append)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("byte-regex-file")
+                    .long("byte-regex-file")
+                    .help("file of patterns matched against the raw bytes, one per line. Empty lines and lines starting with # are skipped")
+                    .required(false)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("extract")
+                    .long("extract")
+                    .help("extract each match of the regex as a string of its own with match, or the named groups of the matches such as user,host")
+                    .required(false)
+                    .value_delimiter(',')
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("regex-file")
+                    .long("regex-file")
+                    .help("file of patterns for searching specific strings, one per line. Empty lines and lines starting with # are skipped")
+                    .required(false)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("encoding")
+                    .short('e')
+                    .long("encoding")
+                    .help("encoding of the strings. ascii (s), utf8 (u), utf16le (l), utf16be (b)")
+                    .default_value("ascii")
+                    .required(false)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("categories")
+                    .short('u')
+                    .long("categories")
+                    .help("unicode categories printable in utf8 and utf16 mode. letters, marks, numbers, symbols, and cjk to read the utf16 code units made of two printable ascii bytes, as most ideographs, kana and hangul are. utf16 strings are ascii only unless categories are given")
+                    .required(false)
+                    .value_delimiter(',')
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("threads")
+                    .short('j')
+                    .long("threads")
+                    .help("number of threads scanning a file, 0 uses every core")
+                    .default_value("1")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                clap::Arg::new("data")
+                    .short('d')
+                    .long("data")
+                    .help("only scan the data sections of object files, other files are scanned whole")
+                    .default_value("false")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("sections")
+                    .long("sections")
+                    .help("only scan the named sections of object files, such as .rodata,.comment, __TEXT,__cstring or STRINGTABLE,VERSIONINFO for PE resources")
+                    .required(false)
+                    .value_delimiter(',')
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("decompress")
+                    .long("decompress")
+                    .help("decompress gzip, zstd, xz and bzip2 inputs before scanning them")
+                    .default_value("true")
+                    .required(false)
+                    .value_parser(clap::value_parser!(bool)),
+            )
+            .arg(
+                clap::Arg::new("archive-depth")
+                    .long("archive-depth")
+                    .help("number of nested zip, tar and ar archives whose members are scanned one by one, 0 scans archives whole")
+                    .default_value("4")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                clap::Arg::new("arch")
+                    .long("arch")
+                    .help("only scan the slices of universal binaries of an architecture such as arm64, all scans every slice on its own")
+                    .required(false)
+                    .value_parser(clap::value_parser!(String)),
+            )
+            .arg(
+                clap::Arg::new("radix")
+                    .short('t')
+                    .long("radix")
+                    .help("print the offset of each string. d (decimal), x (hex), o (octal)")
                     .required(false)
                     .value_parser(clap::value_parser!(String)),
             )
@@ -135,24 +414,61 @@ impl Args {
             split: cmd.get_one::<u64>("split").copied(),
             special: cmd.get_one::<bool>("special").copied(),
             whitespace: cmd.get_one::<bool>("whitespace").copied(),
-            output_format: match cmd.get_one::<String>("format") {
-                Some(f) => Some(f.clone()),
-                _ => None,
-            },
+            output_format: cmd.get_one::<String>("format").cloned(),
             length: cmd.get_one::<bool>("length").copied(),
             line_include: cmd.get_one::<bool>("line").copied(),
-            output: match cmd.get_one::<String>("out") {
-                Some(s) => Some(s.clone()),
-                _ => None,
-            },
-            input: match cmd.get_one::<String>("in") {
-                Some(s) => Some(s.clone()),
-                _ => None,
-            },
-            regex: match cmd.get_one::<String>("regex") {
-                Some(s) => Some(s.clone()),
-                _ => None,
-            },
+            entropy: cmd.get_one::<bool>("entropy").copied(),
+            entropy_min: cmd.get_one::<f64>("min-entropy").copied(),
+            entropy_max: cmd.get_one::<f64>("max-entropy").copied(),
+            classify: cmd.get_one::<bool>("classify").copied(),
+            secrets: cmd.get_one::<bool>("secrets").copied(),
+            rules: cmd
+                .get_many::<String>("rules")
+                .map(|r| r.cloned().collect()),
+            findings_only: cmd.get_one::<bool>("findings-only").copied(),
+            fail_on_findings: cmd.get_one::<bool>("fail-on-findings").copied(),
+            kinds: cmd
+                .get_many::<String>("kinds")
+                .map(|k| k.cloned().collect()),
+            output: cmd.get_one::<String>("out").cloned(),
+            input: cmd
+                .get_many::<String>("in")
+                .map(|i| i.cloned().collect()),
+            regex: cmd
+                .get_many::<String>("regex")
+                .map(|r| r.cloned().collect()),
+            regex_file: cmd.get_one::<String>("regex-file").cloned(),
+            exclude_regex: cmd
+                .get_many::<String>("exclude-regex")
+                .map(|r| r.cloned().collect()),
+            exclude_regex_file: cmd.get_one::<String>("exclude-regex-file").cloned(),
+            byte_regex: cmd
+                .get_many::<String>("byte-regex")
+                .map(|r| r.cloned().collect()),
+            byte_regex_file: cmd.get_one::<String>("byte-regex-file").cloned(),
+            extract: cmd
+                .get_many::<String>("extract")
+                .map(|e| e.cloned().collect()),
+            include: cmd
+                .get_many::<String>("include")
+                .map(|i| i.cloned().collect()),
+            exclude: cmd
+                .get_many::<String>("exclude")
+                .map(|e| e.cloned().collect()),
+            follow_symlinks: cmd.get_one::<bool>("follow").copied(),
+            encoding: cmd.get_one::<String>("encoding").cloned(),
+            radix: cmd.get_one::<String>("radix").cloned(),
+            threads: cmd.get_one::<u64>("threads").copied(),
+            data: cmd.get_one::<bool>("data").copied(),
+            sections: cmd
+                .get_many::<String>("sections")
+                .map(|s| s.cloned().collect()),
+            arch: cmd.get_one::<String>("arch").cloned(),
+            decompress: cmd.get_one::<bool>("decompress").copied(),
+            archive_depth: cmd.get_one::<u64>("archive-depth").copied(),
+            categories: cmd
+                .get_many::<String>("categories")
+                .map(|c| c.cloned().collect()),
         })
     }
 }
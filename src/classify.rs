@@ -0,0 +1,226 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Kinds of values that are recognized inside of the extracted strings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// a scheme followed by `://`, such as `https://example.com/a`
+    Url,
+    /// a dotted IPv4 address
+    Ipv4,
+    /// an IPv6 address, including the `::` shorthand
+    Ipv6,
+    /// an email address
+    Email,
+    /// a lowercase host name under a common top level domain
+    Domain,
+    /// a drive, UNC or environment variable rooted Windows path
+    WindowsPath,
+    /// an absolute Unix path of at least two components
+    UnixPath,
+    /// a Windows registry key under a hive or the software key
+    RegistryKey,
+    /// a GUID, with or without braces
+    Guid,
+    /// a hex digest the size of MD5, SHA-1, SHA-256 or SHA-512
+    Hash,
+}
+
+/// every kind in the order the tags are written in
+pub const KINDS: [Kind; 10] = [
+    Kind::Url,
+    Kind::Ipv4,
+    Kind::Ipv6,
+    Kind::Email,
+    Kind::Domain,
+    Kind::WindowsPath,
+    Kind::UnixPath,
+    Kind::RegistryKey,
+    Kind::Guid,
+    Kind::Hash,
+];
+
+impl Kind {
+    /// name of the kind used in the output and on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Url => "url",
+            Kind::Ipv4 => "ipv4",
+            Kind::Ipv6 => "ipv6",
+            Kind::Email => "email",
+            Kind::Domain => "domain",
+            Kind::WindowsPath => "windows-path",
+            Kind::UnixPath => "unix-path",
+            Kind::RegistryKey => "registry-key",
+            Kind::Guid => "guid",
+            Kind::Hash => "hash",
+        }
+    }
+
+    /// finds a kind by its name, ignoring the case
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace('_', "-");
+        KINDS.into_iter().find(|k| k.name() == name)
+    }
+
+    /// checks if the kind is found anywhere in the string
+    fn found(&self, s: &str) -> bool {
+        match self {
+            Kind::Url => URL.is_match(s),
+            Kind::Ipv4 => tokens(&IPV4, s).any(|t| t.parse::<Ipv4Addr>().is_ok()),
+            // a single colon is a label or a C++ scope, not an address
+            Kind::Ipv6 => tokens(&IPV6, s)
+                .any(|t| t != "::" && t.matches(':').count() >= 2 && t.parse::<Ipv6Addr>().is_ok()),
+            Kind::Email => EMAIL.is_match(s),
+            Kind::Domain => tokens(&DOMAIN, s)
+                .any(|t| TLDS.contains(&t.rsplit('.').next().unwrap_or_default())),
+            Kind::WindowsPath => WINDOWS_PATH.is_match(s),
+            Kind::UnixPath => UNIX_PATH.is_match(s),
+            Kind::RegistryKey => REGISTRY_KEY.is_match(s),
+            Kind::Guid => tokens(&GUID, s).next().is_some(),
+            Kind::Hash => tokens(&HASH, s).next().is_some(),
+        }
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Top level domains a host name must end with, file names such as
+/// `libc.so`, `config.json` or `Makefile.in` would be domains otherwise
+const TLDS: &[&str] = &[
+    "com", "net", "org", "edu", "gov", "mil", "int", "info", "biz", "io", "co", "ai", "app",
+    "dev", "cloud", "xyz", "online", "site", "top", "me", "tv", "us", "uk", "de", "fr", "ru",
+    "cn", "jp", "br", "it", "nl", "es", "au", "ca", "ch", "se", "no", "pl", "eu", "kr",
+    "tw", "cz", "be", "at", "dk", "fi", "ir", "ua", "tk", "gg", "to", "su", "onion", "local",
+    "arpa",
+];
+
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b[A-Za-z][A-Za-z0-9+.-]*://[^\s"'<>]+"#).unwrap()
+});
+static IPV4: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[0-9][0-9.]*[0-9]").unwrap());
+static IPV6: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[0-9A-Fa-f:.]*:[0-9A-Fa-f:.]*").unwrap());
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").unwrap()
+});
+static DOMAIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:[a-z0-9](?:[a-z0-9-]{0,61}[a-z0-9])?\.)+[a-z]{2,63}").unwrap()
+});
+static WINDOWS_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\b[A-Za-z]:\\|\\\\[A-Za-z0-9._$-]+\\|%[A-Za-z_]+%\\)").unwrap()
+});
+static UNIX_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:^|[\s"'=(,])~?(?:/[A-Za-z0-9._+@-]+){2,}/?"#).unwrap()
+});
+static REGISTRY_KEY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:\b(?:HKEY_(?:LOCAL_MACHINE|CURRENT_USER|CLASSES_ROOT|USERS|CURRENT_CONFIG)|HKLM|HKCU|HKCR|HKU|HKCC)\\|\bSOFTWARE\\(?:Microsoft|Wow6432Node|Classes|Policies)\\|\\Registry\\(?:Machine|User)\\)",
+    )
+    .unwrap()
+});
+static GUID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{?[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}\}?").unwrap()
+});
+static HASH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[0-9A-Fa-f]{32}(?:[0-9A-Fa-f]{96}|[0-9A-Fa-f]{32}|[0-9A-Fa-f]{8})?").unwrap()
+});
+
+/// Matches of a pattern that stand on their own, so that a part of a
+/// longer word or number is not taken for a value. The pattern must
+/// match the longest run of its characters.
+fn tokens<'s>(re: &'s Regex, s: &'s str) -> impl Iterator<Item = &'s str> {
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    re.find_iter(s)
+        .filter(move |m| {
+            let before = s[..m.start()].chars().next_back();
+            let after = s[m.end()..].chars().next();
+            !word(before) && !word(after)
+        })
+        .map(|m| m.as_str())
+}
+
+/// Returns the kinds of values found in a string in the order of `KINDS`,
+/// a string holding a URL is usually also tagged with its domain.
+pub fn classify(s: &str) -> Vec<Kind> {
+    KINDS.into_iter().filter(|k| k.found(s)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        let cases: [(Kind, &[&str], &[&str]); 10] = [
+            (Kind::Url, &["see https://example.com/a?b=c", "ftp://host/file"], &["https//example.com", "://nothing"]),
+            (Kind::Ipv4, &["connect to 10.0.0.1:80", "8.8.8.8"], &["version 1.2.3.4.5", "256.1.1.1", "v10.0.0.1"]),
+            (Kind::Ipv6, &["listen on ::1", "fe80::1ff:fe23:4567:890a%eth0", "[2001:db8::8a2e:370:7334]:443"], &[
+                "std::string",
+                "Foo::bar",
+                "label: value",
+                "::",
+                "12:34:56",
+            ]),
+            (Kind::Email, &["mail root@example.org now"], &["user@localhost", "@example.org"]),
+            (Kind::Domain, &["www.example.com", "api.service.io/v1"], &["libc.so.6", "config.json", "Makefile.in"]),
+            (Kind::WindowsPath, &[r"C:\Windows\System32", r"\\server\share", r"%APPDATA%\app"], &["C:/unix/like"]),
+            (Kind::UnixPath, &["/usr/lib/libc.so.6", "path=~/bin/tool"], &["/bin", "a/b/c", "http://host/a/b"]),
+            (Kind::RegistryKey, &[r"HKLM\Software\Vendor", r"SOFTWARE\Microsoft\Windows"], &[r"SOFTWARE\Vendor"]),
+            (Kind::Guid, &["{6B29FC40-CA47-1067-B31D-00DD010662DA}", "6b29fc40-ca47-1067-b31d-00dd010662da"], &[
+                "x6b29fc40-ca47-1067-b31d-00dd010662da",
+                "6b29fc40-ca47-1067-b31d-00dd010662",
+            ]),
+            (Kind::Hash, &["d41d8cd98f00b204e9800998ecf8427e", "sha1 da39a3ee5e6b4b0d3255bfef95601890afd80709"], &[
+                "d41d8cd98f00b204e9800998ecf8427e0",
+                "0xd41d8cd98f00b204e9800998ecf8427e",
+                "d41d8cd98f00b204e9800998ecf8427",
+            ]),
+        ];
+        for (kind, positives, negatives) in cases {
+            for s in positives {
+                assert!(kind.found(s), "{} must be found in {}", kind, s);
+            }
+            for s in negatives {
+                assert!(!kind.found(s), "{} must not be found in {}", kind, s);
+            }
+        }
+    }
+
+    #[test]
+    fn domains() {
+        // only the top level domains of the list are domains
+        for tld in TLDS {
+            assert!(Kind::Domain.found(&format!("host.{}", tld)), "{}", tld);
+        }
+        for name in ["file.txt", "archive.tar", "module.py", "lib.rs", "host.example"] {
+            assert!(!Kind::Domain.found(name), "{}", name);
+        }
+        // host names are lowercase, a file name extension is not a domain
+        assert!(!Kind::Domain.found("README.ME"));
+    }
+
+    #[test]
+    fn token_boundaries() {
+        let boundaries = |s| tokens(&IPV4, s).collect::<Vec<_>>();
+        assert_eq!(boundaries("1.2.3.4 and 5.6.7.8"), vec!["1.2.3.4", "5.6.7.8"]);
+        assert_eq!(boundaries("ip=1.2.3.4,"), vec!["1.2.3.4"]);
+        assert_eq!(boundaries("a1.2.3.4 1.2.3.4b _1.2.3.4 é1.2.3.4"), Vec::<&str>::new());
+        // the longest run is taken, so a part of a number is never a match
+        assert_eq!(tokens(&HASH, &"a".repeat(48)).count(), 0);
+        assert_eq!(tokens(&HASH, &"a".repeat(64)).count(), 1);
+    }
+
+    #[test]
+    fn classified() {
+        assert_eq!(classify("https://www.example.com/a"), vec![Kind::Url, Kind::Domain]);
+        assert_eq!(classify("plain words only"), Vec::new());
+        assert_eq!(Kind::from_name("Windows_Path"), Some(Kind::WindowsPath));
+        assert_eq!(Kind::from_name("path"), None);
+    }
+}
@@ -1,10 +1,154 @@
+use crate::classify::Kind;
+use crate::error::StringerError;
+use crate::rules::Rule;
+use crate::section::{join_names, SectionFilter};
+
 /// Output formats that are supported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OutputFormat {
     JSON,
     XML,
+    YAML,
+    CSV,
+    TSV,
     Literal,
 }
 
+/// Encodings the strings can be extracted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// single byte 7-bit ASCII characters
+    Ascii,
+    /// UTF-8 including valid multibyte sequences
+    Utf8,
+    /// 16-bit little endian code units, including surrogate pairs
+    Utf16Le,
+    /// 16-bit big endian code units, including surrogate pairs
+    Utf16Be,
+}
+
+impl Encoding {
+    /// name of the encoding used in the output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Ascii => "ascii",
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+        }
+    }
+
+    /// converts bytes of a string in this encoding into UTF-8.
+    /// Invalid sequences are replaced with U+FFFD.
+    pub fn to_utf8(&self, bytes: &[u8]) -> Vec<u8> {
+        let unit: fn([u8; 2]) -> u16 = match self {
+            Encoding::Ascii | Encoding::Utf8 => return bytes.to_vec(),
+            Encoding::Utf16Le => u16::from_le_bytes,
+            Encoding::Utf16Be => u16::from_be_bytes,
+        };
+        let units = bytes.chunks_exact(2).map(|u| unit([u[0], u[1]]));
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// number of bytes a UTF-8 string takes in this encoding
+    pub fn encoded_len(&self, s: &str) -> u64 {
+        match self {
+            Encoding::Ascii | Encoding::Utf8 => s.len() as u64,
+            Encoding::Utf16Le | Encoding::Utf16Be => s.encode_utf16().count() as u64 * 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What is taken out of the strings that match the regex patterns,
+/// each piece is a result of its own
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Extract {
+    /// every match of the patterns
+    Matches,
+    /// the named capture groups of the matches
+    Groups(Vec<String>),
+}
+
+/// Radix the offsets of the strings are printed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Radix {
+    Decimal,
+    Hex,
+    Octal,
+}
+
+impl Radix {
+    /// formats an offset in this radix, right aligned like `strings -t`
+    pub fn format(&self, n: u64) -> String {
+        match self {
+            Radix::Decimal => format!("{:>7}", n),
+            Radix::Hex => format!("{:>7x}", n),
+            Radix::Octal => format!("{:>7o}", n),
+        }
+    }
+}
+
+/// Unicode categories of non ASCII characters that are
+/// treated as printable while extracting UTF-8 and UTF-16 strings.
+/// Punctuation follows `special` and space separators
+/// follow `whitespace_include`.
+#[derive(Clone, Copy, Debug)]
+pub struct UnicodeCategories {
+    /// L* letters of any script
+    pub letters: bool,
+    /// M* combining marks such as accents and vowel signs
+    pub marks: bool,
+    /// N* decimal digits, letter and other numbers
+    pub numbers: bool,
+    /// S* math, currency, modifier and other symbols such as emoji
+    pub symbols: bool,
+}
+
+impl Default for UnicodeCategories {
+    fn default() -> Self {
+        UnicodeCategories {
+            letters: true,
+            marks: true,
+            numbers: true,
+            symbols: true,
+        }
+    }
+}
+
+impl UnicodeCategories {
+    /// creates a category set from names such as
+    /// `letters`, `marks`, `numbers` and `symbols`
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self, StringerError> {
+        let mut cats = UnicodeCategories {
+            letters: false,
+            marks: false,
+            numbers: false,
+            symbols: false,
+        };
+        for name in names {
+            match name.as_ref().to_lowercase().as_str() {
+                "letters" | "l" => cats.letters = true,
+                "marks" | "m" => cats.marks = true,
+                "numbers" | "n" => cats.numbers = true,
+                "symbols" | "s" => cats.symbols = true,
+                n => {
+                    return Err(StringerError::Config(format!("unknown unicode category {}", n)));
+                }
+            }
+        }
+        Ok(cats)
+    }
+}
+
 /// Default Window minimum size config
 const WINDOW_MIN_SIZE: u64 = 4;
 /// Default window maximum size config
@@ -17,18 +161,64 @@ const WHITESPACE_INCLUDE: bool = true;
 const OUTPUTFORMAT: OutputFormat = OutputFormat::Literal;
 /// Default string length  output format config
 const LENGTH: bool = false;
-/// Default regex option
-const REGEX: Option<regex::Regex> = None;
+/// By default the entropy of the strings is not in the output
+const ENTROPY: bool = false;
+/// By default strings of any entropy are extracted
+const ENTROPY_MIN: Option<f64> = None;
+/// By default strings of any entropy are extracted
+const ENTROPY_MAX: Option<f64> = None;
+/// By default the strings are not classified
+const CLASSIFY: bool = false;
+/// By default the strings are not checked against secret rules
+const SECRETS: bool = false;
+/// By default the strings without findings are extracted as well
+const FINDINGS_ONLY: bool = false;
+/// By default findings do not change the exit code
+const FAIL_ON_FINDINGS: bool = false;
+/// Default regex option, every string is extracted
+const REGEX: Option<regex::RegexSet> = None;
+/// Default exclude regex option, no string is dropped
+const EXCLUDE_REGEX: Option<regex::RegexSet> = None;
+/// By default the strings that match are extracted whole
+const EXTRACT: Option<Extract> = None;
 /// By default does not treats LF CR as a 
 /// valid character in the extraction process
 const LINE_INCLUDE: bool = false;
 /// Default split option, no splitting
 const SPLIT: u64 = 0;
+/// Default number of bytes pulled from the stream at once
+const CHUNK_SIZE: u64 = 64 * 1024;
+/// By default runs of single byte characters are scanned with the byte table
+const TABLE_SCAN: bool = true;
+/// Default smallest piece of a file that is handed to a scanning thread
+const PIECE_SIZE: u64 = 1024 * 1024;
+/// Default length of the longest match of the byte patterns that is never
+/// cut when a stream is matched a chunk at a time
+const MATCH_OVERLAP: u64 = 64 * 1024;
+/// Default encoding, plain ASCII like the classic strings
+const ENCODING: Encoding = Encoding::Ascii;
+/// By default UTF-16 strings are ASCII only, binary data is often read as other characters
+const UTF16_UNICODE: bool = false;
+/// By default UTF-16 code units made of two printable ASCII bytes are not characters
+const UTF16_CJK: bool = false;
+/// By default offsets are not printed in literal output
+const RADIX: Option<Radix> = None;
+/// Default number of scanning threads, a single thread
+const THREADS: u64 = 1;
+/// By default object files are scanned whole
+const SECTIONS: SectionFilter = SectionFilter::All;
+/// By default every slice of a universal binary is scanned together
+const ARCH: Option<String> = None;
+/// By default compressed inputs are decompressed before they are scanned
+const DECOMPRESS: bool = true;
+/// Default number of nested archives whose members are scanned one by one
+const ARCHIVE_DEPTH: u64 = 4;
 
 /// StringerConfig
 /// This structure specifies the configuration
 /// that are used during the string extraction
 /// in order to customize the process.
+#[derive(Clone)]
 pub struct StringerConfig {
     /// @window_min_size: the minimum size of a string length to start with
     pub window_min_size: u64,
@@ -45,12 +235,82 @@ pub struct StringerConfig {
     pub output_format: OutputFormat,
     /// @length: include the size of the string length in the output
     pub length: bool,
+    /// @entropy: include the Shannon entropy of the string in the output
+    pub entropy: bool,
+    /// @entropy_min: strings with a lower entropy in bits per character are dropped
+    pub entropy_min: Option<f64>,
+    /// @entropy_max: strings with a higher entropy in bits per character are dropped
+    pub entropy_max: Option<f64>,
+    /// @classify: tags the strings with the kinds of values found in them
+    pub classify: bool,
+    /// @kinds: only the strings holding a value of one of the kinds are
+    /// extracted, every string is extracted when empty
+    pub kinds: Vec<Kind>,
+    /// @rules: secret rules the strings are checked against, the
+    /// findings are tagged on the strings
+    pub rules: Vec<Rule>,
+    /// @findings_only: only the strings flagged by the rules are extracted
+    pub findings_only: bool,
+    /// @fail_on_findings: the command line fails once strings are flagged
+    pub fail_on_findings: bool,
     /// splits every string extracted into split capacity
     pub split: u64,
-    /// @regex: regex pattern for matching, optional.
-    pub regex: Option<regex::Regex>,
+    /// @regex: regex patterns for matching, optional. A string is
+    /// extracted if any of the patterns matches it, the id of a
+    /// pattern is its position in the set. Set with `set_patterns`.
+    regex: Option<regex::RegexSet>,
+    /// @regexes: the patterns of the set on their own, which find
+    /// the positions of the matches, built with the set
+    regexes: Vec<regex::Regex>,
+    /// @exclude_regex: regex patterns of strings that are dropped even
+    /// when the regex patterns match them, optional.
+    pub exclude_regex: Option<regex::RegexSet>,
+    /// @byte_regexes: patterns matched against the raw bytes of the input
+    /// instead of the strings, each match is reported on its own
+    pub byte_regexes: Vec<regex::bytes::Regex>,
+    /// @extract: takes the matches of the patterns or their named groups
+    /// out of the strings instead of extracting the strings whole
+    pub extract: Option<Extract>,
     /// @line_include: treats 0x0a LINE FEED, 0x0d CR as a string
     pub line_include: bool,
+    /// @chunk_size: number of bytes read from the stream at once
+    pub chunk_size: u64,
+    /// @table_scan: scans runs of single byte characters with the byte table,
+    /// otherwise every character is checked on its own as before the table
+    pub table_scan: bool,
+    /// @piece_size: smallest piece of a file that is handed to a thread
+    /// when it is scanned on several threads
+    #[doc(hidden)]
+    pub piece_size: u64,
+    /// @match_overlap: length of the longest match of the byte patterns
+    /// that is never cut when a stream is matched a chunk at a time
+    #[doc(hidden)]
+    pub match_overlap: u64,
+    /// @encoding: encoding of the strings that are extracted
+    pub encoding: Encoding,
+    /// @categories: unicode categories that are printable in UTF-8 and UTF-16 mode
+    pub categories: UnicodeCategories,
+    /// @utf16_unicode: characters of the categories outside of ASCII are
+    /// printable in UTF-16 as well, not only in UTF-8
+    pub utf16_unicode: bool,
+    /// @utf16_cjk: UTF-16 code units made of two printable ASCII bytes,
+    /// most CJK ideographs, kana and Hangul, are printable. ASCII text read
+    /// at the wrong alignment or as UTF-16 is made of them too.
+    pub utf16_cjk: bool,
+    /// @radix: prints the offset of each string in the given radix
+    pub radix: Option<Radix>,
+    /// @threads: number of threads scanning a file, 0 uses every core
+    pub threads: u64,
+    /// @sections: sections of object files that are scanned
+    pub sections: SectionFilter,
+    /// @arch: architecture of the slices of universal binaries that are
+    /// scanned, `all` scans every slice on its own
+    pub arch: Option<String>,
+    /// @decompress: decompresses gzip, zstd, xz and bzip2 inputs
+    pub decompress: bool,
+    /// @archive_depth: number of nested zip, tar and ar archives whose
+    /// members are scanned one by one, 0 scans archives as they are
+    pub archive_depth: u64,
 }
 
 impl Default for StringerConfig {
@@ -63,9 +323,35 @@ impl Default for StringerConfig {
             whitespace_include: WHITESPACE_INCLUDE,
             output_format: OUTPUTFORMAT,
             length: LENGTH,
+            entropy: ENTROPY,
+            entropy_min: ENTROPY_MIN,
+            entropy_max: ENTROPY_MAX,
+            classify: CLASSIFY,
+            kinds: Vec::new(),
+            rules: Vec::new(),
+            findings_only: FINDINGS_ONLY,
+            fail_on_findings: FAIL_ON_FINDINGS,
             regex: REGEX,
+            regexes: Vec::new(),
+            exclude_regex: EXCLUDE_REGEX,
+            byte_regexes: Vec::new(),
+            extract: EXTRACT,
             line_include: LINE_INCLUDE,
             split: SPLIT,
+            chunk_size: CHUNK_SIZE,
+            table_scan: TABLE_SCAN,
+            piece_size: PIECE_SIZE,
+            match_overlap: MATCH_OVERLAP,
+            encoding: ENCODING,
+            categories: UnicodeCategories::default(),
+            utf16_unicode: UTF16_UNICODE,
+            utf16_cjk: UTF16_CJK,
+            radix: RADIX,
+            threads: THREADS,
+            sections: SECTIONS,
+            arch: ARCH,
+            decompress: DECOMPRESS,
+            archive_depth: ARCHIVE_DEPTH,
         }
     }
 }
@@ -77,67 +363,240 @@ impl StringerConfig {
     }
 
     /// includes special characters in the extracted string
-    pub fn special_include(self: &mut Self, opt: bool) {
+    pub fn special_include(&mut self, opt: bool) {
         self.special = opt;
     }
 
     /// changes the minimum window size to provided size
-    pub fn set_window_min_size(self: &mut Self, ws: u64) {
+    pub fn set_window_min_size(&mut self, ws: u64) {
         self.window_min_size = ws;
     }
 
     /// changes the maximum window size to provided size
-    pub fn set_window_max_size(self: &mut Self, ws: u64) {
+    pub fn set_window_max_size(&mut self, ws: u64) {
         self.window_max_size = ws;
     }
 
     /// makes whitespace to be included in the extraction
-    pub fn whitespace_include(self: &mut Self, opt: bool) {
+    pub fn whitespace_include(&mut self, opt: bool) {
         self.whitespace_include = opt;
     }
 
     /// sets the output format to speci
-    pub fn set_output_format(self: &mut Self, opt: OutputFormat) {
+    pub fn set_output_format(&mut self, opt: OutputFormat) {
         self.output_format = opt;
     }
 
     /// enable length to be included in the output
-    pub fn length_include(self: &mut Self, opt: bool) {
+    pub fn length_include(&mut self, opt: bool) {
         self.length = opt;
     }
 
+    /// enable the entropy of the string to be included in the output
+    pub fn entropy_include(&mut self, opt: bool) {
+        self.entropy = opt;
+    }
+
+    /// drops the strings whose entropy is below the minimum
+    pub fn set_entropy_min(&mut self, opt: Option<f64>) {
+        self.entropy_min = opt;
+    }
+
+    /// drops the strings whose entropy is above the maximum
+    pub fn set_entropy_max(&mut self, opt: Option<f64>) {
+        self.entropy_max = opt;
+    }
+
+    /// tags the strings with the kinds of values found in them
+    pub fn classify(&mut self, opt: bool) {
+        self.classify = opt;
+    }
+
+    /// only extracts the strings holding a value of one of the kinds
+    pub fn set_kinds(&mut self, opt: Vec<Kind>) {
+        self.kinds = opt;
+    }
+
+    /// checks if the strings are classified, which filtering them by kind needs
+    pub fn classified(&self) -> bool {
+        self.classify || !self.kinds.is_empty()
+    }
+
+    /// sets the secret rules the strings are checked against
+    pub fn set_rules(&mut self, opt: Vec<Rule>) {
+        self.rules = opt;
+    }
+
+    /// only extracts the strings flagged by the secret rules
+    pub fn findings_only(&mut self, opt: bool) {
+        self.findings_only = opt;
+    }
+
+    /// fails the command line once strings are flagged by the secret rules
+    pub fn fail_on_findings(&mut self, opt: bool) {
+        self.fail_on_findings = opt;
+    }
+
     /// makes LINE FEED AND CR to be treated as string
-    pub fn line_include(self: &mut Self, opt: bool) {
+    pub fn line_include(&mut self, opt: bool) {
         self.line_include = opt;
     }
 
     /// splits the provided string into segments
-    pub fn split(self: &mut Self, opt: u64) {
+    pub fn split(&mut self, opt: u64) {
         self.split = opt;
     }
 
-    /// set regex expression
-    pub fn regex(self: &mut Self, opt: String) -> Result<(), crate::error::StringerError> {
-        let reg = regex::Regex::new(&opt);
-        match reg {
-            Ok(r) => {
-                self.regex = Some(r);
-            }
-            Err(_) => {
-                return Err(crate::error::StringerError::new(
-                    "unable to compile provided regex".to_string(),
-                ));
-            }
+    /// changes the number of bytes read from the stream at once
+    pub fn set_chunk_size(&mut self, size: u64) {
+        self.chunk_size = size;
+    }
+
+    /// scans with the byte table or checks every character on its own
+    pub fn set_table_scan(&mut self, opt: bool) {
+        self.table_scan = opt;
+    }
+
+    /// changes the smallest piece of a file that is handed to a thread
+    #[doc(hidden)]
+    pub fn set_piece_size(&mut self, size: u64) {
+        self.piece_size = size;
+    }
+
+    /// changes the length of the longest match of the byte patterns that is never cut
+    #[doc(hidden)]
+    pub fn set_match_overlap(&mut self, size: u64) {
+        self.match_overlap = size;
+    }
+
+    /// sets the encoding of the strings to extract
+    pub fn set_encoding(&mut self, opt: Encoding) {
+        self.encoding = opt;
+    }
+
+    /// sets the unicode categories that are treated as printable
+    pub fn set_categories(&mut self, opt: UnicodeCategories) {
+        self.categories = opt;
+    }
+
+    /// reads the characters of the categories in UTF-16 as well
+    pub fn set_utf16_unicode(&mut self, opt: bool) {
+        self.utf16_unicode = opt;
+    }
+
+    /// reads UTF-16 code units made of two printable ASCII bytes as characters
+    pub fn set_utf16_cjk(&mut self, opt: bool) {
+        self.utf16_cjk = opt;
+    }
+
+    /// sets the radix the offsets are printed in
+    pub fn set_radix(&mut self, opt: Option<Radix>) {
+        self.radix = opt;
+    }
+
+    /// sets the number of threads scanning a file, 0 uses every core
+    pub fn set_threads(&mut self, opt: u64) {
+        self.threads = opt;
+    }
+
+    /// sets the sections of object files that are scanned
+    pub fn set_sections(&mut self, opt: SectionFilter) {
+        self.sections = opt;
+    }
+
+    /// sets the architecture of the slices of universal binaries that are scanned
+    pub fn set_arch(&mut self, opt: Option<String>) {
+        self.arch = opt;
+    }
+
+    /// decompresses compressed inputs before they are scanned
+    pub fn decompress(&mut self, opt: bool) {
+        self.decompress = opt;
+    }
+
+    /// sets the number of nested archives whose members are scanned one by one
+    pub fn set_archive_depth(&mut self, opt: u64) {
+        self.archive_depth = opt;
+    }
+
+    /// checks if object files are scanned by sections or slices instead of whole
+    pub fn sectioned(&self) -> bool {
+        self.sections != SectionFilter::All || self.arch.is_some()
+    }
+
+    /// adds a regex pattern to the patterns, its id is the number of patterns before it
+    pub fn regex(&mut self, opt: String) -> Result<(), StringerError> {
+        let mut patterns = self.regex.as_ref().map(|r| r.patterns().to_vec()).unwrap_or_default();
+        patterns.push(opt);
+        self.set_patterns(patterns)
+    }
+
+    /// sets the regex patterns, none extracts every string
+    pub fn set_patterns(&mut self, opt: Vec<String>) -> Result<(), StringerError> {
+        self.regexes = opt.iter().map(|p| regex::Regex::new(p)).collect::<Result<_, _>>()?;
+        self.regex = match opt.is_empty() {
+            true => None,
+            false => Some(regex::RegexSet::new(opt)?),
         };
         Ok(())
     }
+
+    /// the regex patterns as a set, none when every string is extracted
+    pub fn pattern_set(&self) -> Option<&regex::RegexSet> {
+        self.regex.as_ref()
+    }
+
+    /// the regex patterns on their own, in the order of their ids
+    pub fn pattern_regexes(&self) -> &[regex::Regex] {
+        &self.regexes
+    }
+
+    /// sets the regex patterns of the strings that are dropped, none drops no string
+    pub fn set_exclude_patterns(&mut self, opt: Vec<String>) -> Result<(), StringerError> {
+        self.exclude_regex = match opt.is_empty() {
+            true => None,
+            false => Some(regex::RegexSet::new(opt)?),
+        };
+        Ok(())
+    }
+
+    /// sets the patterns matched against the raw bytes of the input,
+    /// none segments the input into strings as usual
+    pub fn set_byte_patterns(&mut self, opt: Vec<String>) -> Result<(), StringerError> {
+        self.byte_regexes = opt.iter().map(|p| regex::bytes::Regex::new(p)).collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    /// checks if the input is matched by byte patterns instead of being segmented into strings
+    pub fn raw(&self) -> bool {
+        !self.byte_regexes.is_empty()
+    }
+
+    /// takes the matches of the patterns or their named groups out of the strings
+    pub fn set_extract(&mut self, opt: Option<Extract>) {
+        self.extract = opt;
+    }
 }
 
-impl From<crate::args::Args> for StringerConfig {
+/// reads the regex patterns of a file, one per line, with either
+/// line ending. Empty lines and lines starting with # are skipped.
+fn read_patterns(path: &str) -> Result<Vec<String>, StringerError> {
+    let text = std::fs::read_to_string(path).map_err(|e| StringerError::from(e).with_path(path))?;
+    Ok(text
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+impl TryFrom<crate::args::Args> for StringerConfig {
+    type Error = StringerError;
+
     /// Converts arguments provided to stringer into config
     /// Arguments that are not provided are set to their default values
-    /// NOTE: If the provided regex is not valid then the regex will be empty.
-    fn from(value: crate::args::Args) -> Self {
+    /// Unknown names and invalid regex are returned as errors.
+    fn try_from(value: crate::args::Args) -> Result<Self, Self::Error> {
         let mut conf = StringerConfig::default();
 
         conf.set_window_min_size(match value.window_min_size {
@@ -165,6 +624,16 @@ impl From<crate::args::Args> for StringerConfig {
             _ => LENGTH,
         });
 
+        conf.entropy_include(match value.entropy {
+            Some(e) => e,
+            _ => ENTROPY,
+        });
+
+        conf.classify(match value.classify {
+            Some(c) => c,
+            _ => CLASSIFY,
+        });
+
         conf.line_include(match value.line_include {
             Some(l) => { l },
             _ => LINE_INCLUDE
@@ -175,23 +644,212 @@ impl From<crate::args::Args> for StringerConfig {
             _ => { SPLIT }
         });
 
+        conf.decompress(match value.decompress {
+            Some(d) => d,
+            _ => DECOMPRESS,
+        });
+
+        conf.set_archive_depth(match value.archive_depth {
+            Some(d) => d,
+            _ => ARCHIVE_DEPTH,
+        });
+
+        conf.set_threads(match value.threads {
+            Some(t) => t,
+            _ => THREADS,
+        });
+
         conf.output_format = match value.output_format {
             Some(f) => match f.to_lowercase().as_str() {
                 "literal" => OutputFormat::Literal,
                 "json" => OutputFormat::JSON,
                 "xml" => OutputFormat::XML,
-                _ => OutputFormat::Literal,
+                "yaml" | "yml" => OutputFormat::YAML,
+                "csv" => OutputFormat::CSV,
+                "tsv" => OutputFormat::TSV,
+                f => {
+                    return Err(StringerError::Config(format!("unknown output format {}", f)));
+                }
             },
             _ => OUTPUTFORMAT,
         };
 
-        conf.regex = match value.regex {
-            Some(r) => match regex::Regex::new(&r) {
-                Ok(r) => Some(r),
-                Err(_) => None,
+        conf.encoding = match value.encoding {
+            Some(e) => match e.to_lowercase().as_str() {
+                "ascii" | "s" => Encoding::Ascii,
+                "utf8" | "utf-8" | "u" => Encoding::Utf8,
+                "utf16le" | "utf-16le" | "l" => Encoding::Utf16Le,
+                "utf16be" | "utf-16be" | "b" => Encoding::Utf16Be,
+                e => {
+                    return Err(StringerError::Encoding(format!("unknown encoding {}", e)));
+                }
             },
-            _ => None,
+            _ => ENCODING,
+        };
+
+        conf.radix = match value.radix {
+            Some(r) => match r.to_lowercase().as_str() {
+                "d" | "dec" | "decimal" => Some(Radix::Decimal),
+                "x" | "hex" => Some(Radix::Hex),
+                "o" | "oct" | "octal" => Some(Radix::Octal),
+                r => {
+                    return Err(StringerError::Config(format!("unknown radix {}", r)));
+                }
+            },
+            _ => RADIX,
+        };
+
+        // named sections take precedence over the data sections
+        conf.sections = match (value.sections, value.data) {
+            (Some(n), _) => SectionFilter::Names(join_names(&n)),
+            (None, Some(true)) => SectionFilter::Data,
+            _ => SECTIONS,
         };
-        return conf;
+
+        conf.arch = match value.arch {
+            Some(a) if crate::macho::is_arch(&a) => Some(a),
+            Some(a) => {
+                return Err(StringerError::Config(format!("unknown architecture {}", a)));
+            }
+            None => ARCH,
+        };
+
+        conf.entropy_min = value.entropy_min.or(ENTROPY_MIN);
+        conf.entropy_max = value.entropy_max.or(ENTROPY_MAX);
+        if let (Some(min), Some(max)) = (conf.entropy_min, conf.entropy_max) {
+            if min > max {
+                return Err(StringerError::Config(format!(
+                    "minimum entropy {} is above the maximum entropy {}", min, max
+                )));
+            }
+        }
+
+        if let Some(k) = value.kinds {
+            let kinds = k
+                .iter()
+                .map(|n| Kind::from_name(n).ok_or_else(|| StringerError::Config(format!("unknown kind {}", n))))
+                .collect::<Result<_, _>>()?;
+            conf.set_kinds(kinds);
+        }
+
+        // the rules of the files are checked after the bundled ones
+        let mut rules = match value.secrets.unwrap_or(SECRETS) {
+            true => crate::rules::bundled(),
+            false => Vec::new(),
+        };
+        for path in value.rules.unwrap_or_default() {
+            rules.extend(crate::rules::load(path)?);
+        }
+        conf.set_rules(rules);
+
+        conf.findings_only(match value.findings_only {
+            Some(f) => f,
+            _ => FINDINGS_ONLY,
+        });
+
+        conf.fail_on_findings(match value.fail_on_findings {
+            Some(f) => f,
+            _ => FAIL_ON_FINDINGS,
+        });
+
+        // choosing the categories reads them in UTF-16 too, and
+        // `cjk` among them reads the ideographs of ASCII byte pairs
+        if let Some(c) = value.categories {
+            let (cjk, names): (Vec<String>, Vec<String>) = c
+                .into_iter()
+                .partition(|n| matches!(n.to_lowercase().as_str(), "cjk" | "c"));
+            conf.categories = UnicodeCategories::from_names(&names)?;
+            conf.set_utf16_unicode(true);
+            conf.set_utf16_cjk(!cjk.is_empty());
+        }
+
+        // the patterns of the files come after the ones of the command line
+        let mut patterns = value.regex.unwrap_or_default();
+        if let Some(path) = value.regex_file {
+            patterns.extend(read_patterns(&path)?);
+        }
+        conf.set_patterns(patterns)?;
+
+        let mut exclude = value.exclude_regex.unwrap_or_default();
+        if let Some(path) = value.exclude_regex_file {
+            exclude.extend(read_patterns(&path)?);
+        }
+        conf.set_exclude_patterns(exclude)?;
+
+        let mut bytes = value.byte_regex.unwrap_or_default();
+        if let Some(path) = value.byte_regex_file {
+            bytes.extend(read_patterns(&path)?);
+        }
+        conf.set_byte_patterns(bytes)?;
+
+        conf.extract = match value.extract {
+            Some(e) if e.len() == 1 && e[0].eq_ignore_ascii_case("match") => Some(Extract::Matches),
+            Some(names) => Some(Extract::Groups(names)),
+            None => EXTRACT,
+        };
+        match conf.extract {
+            Some(_) if conf.regexes.is_empty() => {
+                return Err(StringerError::Config("extracting matches needs a regex".to_string()));
+            }
+            Some(Extract::Groups(ref names)) => {
+                let named = |n: &String| {
+                    conf.regexes.iter().any(|r| r.capture_names().flatten().any(|c| c == n))
+                };
+                if let Some(n) = names.iter().find(|n| !named(n)) {
+                    return Err(StringerError::Config(format!("no regex has a group named {}", n)));
+                }
+            }
+            _ => {}
+        }
+        // the byte patterns find the results themselves
+        if conf.raw() && (conf.regex.is_some() || conf.extract.is_some()) {
+            return Err(StringerError::Config(
+                "byte regexes cannot be combined with --regex or --extract".to_string(),
+            ));
+        }
+        Ok(conf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// writes a pattern file into the temporary directory
+    fn pattern_file(name: &str, text: &str) -> String {
+        let path = std::env::temp_dir().join(format!("stringer-{}-{}", std::process::id(), name));
+        std::fs::write(&path, text).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn pattern_files() {
+        let expected = vec!["GLIBC_[0-9.]+".to_string(), "^malloc$".to_string(), " spaced ".to_string()];
+        for (name, ending) in [("lf.txt", "\n"), ("crlf.txt", "\r\n")] {
+            let text = ["# symbols", "GLIBC_[0-9.]+", "", "^malloc$", "#", " spaced "].join(ending);
+            // the last line may have its own ending or a part of one
+            for text in [text.clone(), text.clone() + ending, text + "\r"] {
+                let path = pattern_file(name, &text);
+                let patterns = read_patterns(&path).unwrap();
+                std::fs::remove_file(&path).unwrap();
+                assert_eq!(patterns, expected, "{}", name);
+            }
+        }
+
+        // the patterns get their ids in the order they are added
+        let mut config = StringerConfig::default();
+        config.set_patterns(expected.clone()).unwrap();
+        config.regex("last".to_string()).unwrap();
+        let set = config.pattern_set().unwrap();
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.matches("GLIBC_2.34 last").into_iter().collect::<Vec<_>>(), vec![0, 3]);
+    }
+
+    #[test]
+    fn missing_pattern_file() {
+        let path = std::env::temp_dir().join(format!("stringer-{}-missing.txt", std::process::id()));
+        let err = read_patterns(&path.to_string_lossy()).unwrap_err();
+        assert!(matches!(err, StringerError::Io { path: Some(ref p), .. } if *p == path), "{}", err);
+        assert_eq!(err.exit_code(), 5);
     }
 }
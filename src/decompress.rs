@@ -0,0 +1,77 @@
+use std::io::Read;
+
+/// Most nested compressions that are decoded, a file that still looks
+/// compressed after that is scanned as it is
+pub const MAX_DEPTH: usize = 8;
+/// Number of bytes needed to detect every compression format
+pub const MAGIC_LEN: usize = 6;
+/// Number of bytes decoded to check that a stream with a magic number
+/// is compressed, enough for the first blocks of every format
+pub const PROBE_LEN: usize = 64 * 1024;
+
+/// Compression formats that are detected by their magic numbers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip and .tar.gz, one or more deflate members
+    Gzip,
+    /// Zstandard frames
+    Zstd,
+    /// xz containers of LZMA2 streams
+    Xz,
+    /// bzip2 streams
+    Bzip2,
+}
+
+impl Compression {
+    /// name of the compression used in the output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Xz => "xz",
+            Compression::Bzip2 => "bzip2",
+        }
+    }
+
+    /// detects the compression of a stream from its first bytes
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        match head {
+            // the magic number is followed by deflate, the only method
+            [0x1f, 0x8b, 0x08, ..] => Some(Compression::Gzip),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Compression::Zstd),
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some(Compression::Xz),
+            // the block size follows the magic number
+            [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(Compression::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// Checks that the first bytes of a stream with the magic number of the
+    /// compression are decoded, since text and binary files can start with
+    /// the magic number too. The first byte is decoded from the head, which
+    /// may end before the first block when it is not the whole stream.
+    pub fn probe(&self, head: &[u8], whole: bool) -> bool {
+        let mut byte = [0; 1];
+        match self.decoder(head).and_then(|mut d| d.read(&mut byte)) {
+            Ok(_) => true,
+            Err(e) => e.kind() == std::io::ErrorKind::UnexpectedEof && !whole,
+        }
+    }
+
+    /// Wraps a compressed stream into a reader of the decompressed bytes.
+    /// Streams made of several members or frames are decoded whole.
+    pub fn decoder<'a, R: Read + 'a>(&self, r: R) -> std::io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(r)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(r)?),
+            Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(r)),
+            Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(r)),
+        })
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,191 @@
+use goblin::container::{Container, Ctx, Endian};
+use goblin::elf::section_header::{SectionHeader, SHF_STRINGS, SHN_XINDEX, SHT_NULL};
+use goblin::elf::Elf;
+use goblin::strtab::Strtab;
+
+use crate::section::Section;
+
+/// Magic number every ELF file starts with
+pub const MAGIC: &[u8; 4] = b"\x7fELF";
+
+/// Returns the sections of an ELF file that have contents in the file.
+/// Only the headers are parsed, the symbols and the dynamic
+/// linking information are not needed to find the sections.
+/// Sections that are loaded in memory and not executable, and string
+/// sections like `.comment`, are the data sections.
+pub fn sections(data: &[u8]) -> Result<Vec<Section>, goblin::error::Error> {
+    let header = Elf::parse_header(data)?;
+    let elf = Elf::lazy_parse(header)?;
+    let container = match elf.is_64 {
+        true => Container::Big,
+        false => Container::Little,
+    };
+    let ctx = Ctx::new(container, Endian::from(elf.little_endian));
+
+    let headers = SectionHeader::parse(data, header.e_shoff as usize, header.e_shnum as usize, ctx)?;
+
+    // names of the sections are kept in a section of their own, its
+    // index is in the first header when it does not fit the file header
+    let mut index = header.e_shstrndx as usize;
+    if index == SHN_XINDEX as usize {
+        index = headers.first().map_or(0, |h| h.sh_link as usize);
+    }
+    let names = match headers.get(index).and_then(|h| h.file_range()) {
+        Some(r) => Strtab::parse(data, r.start, r.len(), 0)?,
+        None => Strtab::default(),
+    };
+
+    Ok(headers
+        .iter()
+        .filter(|h| h.sh_type != SHT_NULL)
+        .filter_map(|h| {
+            let range = h.file_range()?;
+            // sections that reach past the end of a truncated file
+            if range.is_empty() || range.end > data.len() {
+                return None;
+            }
+
+            Some(Section {
+                name: names.get_at(h.sh_name).unwrap_or_default().to_string(),
+                offset: range.start as u64,
+                size: range.len() as u64,
+                address: h.is_alloc().then_some(h.sh_addr),
+                data: !h.is_executable() &&
+                    (h.is_alloc() || h.sh_flags & SHF_STRINGS as u64 != 0),
+                encoding: None,
+                slice: None,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StringerConfig;
+    use crate::section::SectionFilter;
+    use crate::Stringer;
+    use goblin::elf::section_header::{SHF_ALLOC, SHF_EXECINSTR, SHF_MERGE, SHF_WRITE, SHT_PROGBITS, SHT_STRTAB};
+
+    /// a little endian ELF64 object with the sections, given as their
+    /// name, flags, address and contents, and the table of their names
+    fn elf_of(sections: &[(&str, u32, u64, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0; 64];
+        let mut names = b"\0.shstrtab\0".to_vec();
+        let mut headers = vec![[0u8; 64]];
+        let header = |name: usize, kind: u32, flags: u64, addr: u64, offset: usize, size: usize| {
+            let mut h = [0u8; 64];
+            h[0..4].copy_from_slice(&(name as u32).to_le_bytes());
+            h[4..8].copy_from_slice(&kind.to_le_bytes());
+            h[8..16].copy_from_slice(&flags.to_le_bytes());
+            h[16..24].copy_from_slice(&addr.to_le_bytes());
+            h[24..32].copy_from_slice(&(offset as u64).to_le_bytes());
+            h[32..40].copy_from_slice(&(size as u64).to_le_bytes());
+            h[48..56].copy_from_slice(&1u64.to_le_bytes());
+            h
+        };
+        for (name, flags, addr, contents) in sections {
+            headers.push(header(names.len(), SHT_PROGBITS, *flags as u64, *addr, data.len(), contents.len()));
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+            data.extend_from_slice(contents);
+        }
+        headers.push(header(1, SHT_STRTAB, 0, 0, data.len(), names.len()));
+        data.extend_from_slice(&names);
+
+        let shoff = data.len() as u64;
+        for h in &headers {
+            data.extend_from_slice(h);
+        }
+        data[..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+        data[16..18].copy_from_slice(&1u16.to_le_bytes());
+        data[18..20].copy_from_slice(&62u16.to_le_bytes());
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+        data[40..48].copy_from_slice(&shoff.to_le_bytes());
+        data[52..54].copy_from_slice(&64u16.to_le_bytes());
+        data[58..60].copy_from_slice(&64u16.to_le_bytes());
+        data[60..62].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+        data[62..64].copy_from_slice(&(headers.len() as u16 - 1).to_le_bytes());
+        data
+    }
+
+    fn sample() -> Vec<u8> {
+        elf_of(&[
+            (".text", SHF_ALLOC | SHF_EXECINSTR, 0x1000, b"\x90code string\x90"),
+            (".rodata", SHF_ALLOC, 0x2000, b"\0read only string\0"),
+            (".data", SHF_ALLOC | SHF_WRITE, 0x3000, b"\0\0writable string\0"),
+            (".comment", SHF_MERGE | SHF_STRINGS, 0, b"GCC: (GNU) 14.2\0"),
+            (".note", 0, 0, b"\0note string\0"),
+        ])
+    }
+
+    /// the strings of the selected sections with their section and address
+    fn strings(mut s: Stringer<'_>, filter: SectionFilter) -> Vec<(String, Option<String>, Option<u64>, u64)> {
+        let mut config = StringerConfig::default();
+        config.set_sections(filter);
+        config.set_chunk_size(16);
+        s.set_config(config);
+        s.read_strings_sections().unwrap();
+        s.results
+            .iter()
+            .map(|r| (r.as_str().to_string(), r.section().map(String::from), r.address(), r.offset()))
+            .collect()
+    }
+
+    #[test]
+    fn data_sections() {
+        let data = sample();
+        let names: Vec<(String, bool)> = sections(&data).unwrap().into_iter().map(|s| (s.name, s.data)).collect();
+        assert_eq!(names, vec![
+            (".text".to_string(), false),
+            (".rodata".to_string(), true),
+            (".data".to_string(), true),
+            (".comment".to_string(), true),
+            (".note".to_string(), false),
+            (".shstrtab".to_string(), false),
+        ]);
+
+        let at = |s: &str| data.windows(s.len()).position(|w| w == s.as_bytes()).unwrap() as u64;
+        let expected = vec![
+            ("read only string".to_string(), Some(".rodata".to_string()), Some(0x2001), at("read only string")),
+            ("writable string".to_string(), Some(".data".to_string()), Some(0x3002), at("writable string")),
+            ("GCC: (GNU) 14.2".to_string(), Some(".comment".to_string()), None, at("GCC: (GNU) 14.2")),
+        ];
+        assert_eq!(strings(Stringer::from_slice(&data), SectionFilter::Data), expected);
+        // a stream of an object file is read whole to find its sections
+        assert_eq!(strings(Stringer::from_reader(&data[..]), SectionFilter::Data), expected);
+    }
+
+    #[test]
+    fn named_sections() {
+        let data = sample();
+        let filter = SectionFilter::Names(vec![".text".to_string(), ".note".to_string()]);
+        let found: Vec<(String, Option<String>, Option<u64>)> = strings(Stringer::from_slice(&data), filter)
+            .into_iter()
+            .map(|(s, section, address, _)| (s, section, address))
+            .collect();
+        assert_eq!(found, vec![
+            ("code string".to_string(), Some(".text".to_string()), Some(0x1001)),
+            ("note string".to_string(), Some(".note".to_string()), None),
+        ]);
+    }
+
+    #[test]
+    fn other_inputs() {
+        // inputs that are not object files are scanned whole
+        let mut data = b"\x7fELF truncated header string\0".to_vec();
+        let found = strings(Stringer::from_slice(&data), SectionFilter::Data);
+        assert_eq!(found, vec![("ELF truncated header string".to_string(), None, None, 1)]);
+
+        // and a stream is not read into memory to find out
+        data = b"plain text that is not an object file\n".repeat(64);
+        let mut s = Stringer::from_reader(&data[..]);
+        let mut config = StringerConfig::default();
+        config.set_sections(SectionFilter::Data);
+        config.set_chunk_size(16);
+        s.set_config(config);
+        assert!(!s.sectioned().unwrap());
+        assert!(s.buffer.len() < 64);
+        assert_eq!(s.count(), 64);
+    }
+}
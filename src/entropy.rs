@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+/// Shannon entropy of a string in bits per character. A run of a single
+/// repeated character is 0, a string of `n` distinct characters is log2 `n`.
+pub fn shannon(s: &str) -> f64 {
+    // ASCII characters are counted without hashing
+    let mut ascii = [0u64; 128];
+    let mut other: HashMap<char, u64> = HashMap::new();
+    let mut total = 0u64;
+    for c in s.chars() {
+        total += 1;
+        match c.is_ascii() {
+            true => ascii[c as usize] += 1,
+            false => *other.entry(c).or_default() += 1,
+        }
+    }
+    if total == 0 {
+        return 0.0;
+    }
+
+    ascii
+        .into_iter()
+        .chain(other.into_values())
+        .filter(|&n| n > 0)
+        .fold(0.0, |h, n| {
+            let p = n as f64 / total as f64;
+            h - p * p.log2()
+        })
+}
+
+/// rounds an entropy to the two decimals it is written with
+pub fn round(h: f64) -> f64 {
+    (h * 100.0).round() / 100.0
+}
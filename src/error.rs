@@ -1,29 +1,130 @@
 /// Error that is retruned by stringer
 #[derive(Debug)]
-pub struct StringerError {
-    msg: String,
+pub enum StringerError {
+    /// reading an input failed, the path is known for files
+    Io {
+        path: Option<std::path::PathBuf>,
+        source: std::io::Error,
+    },
+    /// a regex pattern could not be compiled
+    Regex(regex::Error),
+    /// an option or a combination of options is not valid
+    Config(String),
+    /// an encoding is unknown or the bytes are not valid in it
+    Encoding(String),
+    /// writing the results to the output failed
+    Output(Box<dyn std::error::Error + Send + Sync>),
+    /// strings were flagged by the secret rules
+    Findings(u64),
 }
 
 impl std::fmt::Display for StringerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("StringerError {{ msg: {} }}", self.msg))
+        match self {
+            StringerError::Io { path: Some(p), source } => {
+                f.write_fmt(format_args!("cannot read {}: {}", p.display(), source))
+            }
+            StringerError::Io { path: None, source } => {
+                f.write_fmt(format_args!("cannot read input: {}", source))
+            }
+            StringerError::Regex(e) => f.write_fmt(format_args!("invalid regex: {}", e)),
+            StringerError::Config(msg) => f.write_fmt(format_args!("invalid config: {}", msg)),
+            StringerError::Encoding(msg) => f.write_fmt(format_args!("encoding error: {}", msg)),
+            StringerError::Output(e) => f.write_fmt(format_args!("cannot write output: {}", e)),
+            StringerError::Findings(n) => f.write_fmt(format_args!("{} strings flagged by the secret rules", n)),
+        }
+    }
+}
+
+impl std::error::Error for StringerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StringerError::Io { source, .. } => Some(source),
+            StringerError::Regex(e) => Some(e),
+            StringerError::Output(e) => Some(e.as_ref()),
+            StringerError::Config(_) | StringerError::Encoding(_) |
+            StringerError::Findings(_) => None,
+        }
     }
 }
 
-/// Default stringer error
-impl Default for StringerError {
-    fn default() -> Self {
-        StringerError {
-            msg: "StringerError {}".to_string(),
+impl From<std::io::Error> for StringerError {
+    /// an I/O error while reading an input that has no path
+    fn from(value: std::io::Error) -> Self {
+        StringerError::Io {
+            path: None,
+            source: value,
         }
     }
 }
 
+impl From<regex::Error> for StringerError {
+    fn from(value: regex::Error) -> Self {
+        StringerError::Regex(value)
+    }
+}
+
 impl StringerError {
-    /// creates a new stringer error type with a message
-    pub fn new(msg: String) -> StringerError {
-        Self { msg }
+    /// creates an output error from any error of the writers
+    pub fn output<E>(e: E) -> StringerError
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        StringerError::Output(e.into())
+    }
+
+    /// attaches the path of the input to an I/O error that has none
+    pub fn with_path<P: Into<std::path::PathBuf>>(self, p: P) -> StringerError {
+        match self {
+            StringerError::Io { path: None, source } => StringerError::Io {
+                path: Some(p.into()),
+                source,
+            },
+            e => e,
+        }
+    }
+
+    /// Checks if the output was closed by its reader, as in `stringer | head`.
+    /// The I/O error may be wrapped anywhere in the errors of the writers.
+    pub fn broken_pipe(&self) -> bool {
+        let mut e: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(err) = e {
+            if io_error_kind(err) == Some(std::io::ErrorKind::BrokenPipe) {
+                return true;
+            }
+            e = err.source();
+        }
+        false
+    }
+
+    /// exit code of the command line for the error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StringerError::Config(_) => 2,
+            StringerError::Regex(_) => 3,
+            StringerError::Encoding(_) => 4,
+            StringerError::Io { .. } => 5,
+            StringerError::Output(_) => 6,
+            StringerError::Findings(_) => 7,
+        }
     }
 }
 
-impl std::error::Error for StringerError {}
+/// the kind of the I/O error that an error of the writers stands for
+fn io_error_kind(err: &(dyn std::error::Error + 'static)) -> Option<std::io::ErrorKind> {
+    if let Some(e) = err.downcast_ref::<std::io::Error>() {
+        return Some(e.kind());
+    }
+    // quick-xml shares its I/O errors while serde_json
+    // and csv keep them out of the chain of sources
+    if let Some(e) = err.downcast_ref::<std::sync::Arc<std::io::Error>>() {
+        return Some(e.kind());
+    }
+    if let Some(e) = err.downcast_ref::<serde_json::Error>() {
+        return e.io_error_kind();
+    }
+    match err.downcast_ref::<csv::Error>().map(|e| e.kind()) {
+        Some(csv::ErrorKind::Io(e)) => Some(e.kind()),
+        _ => None,
+    }
+}
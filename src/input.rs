@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::StringerError;
+
+/// Default symlink policy, links are not followed
+const FOLLOW_SYMLINKS: bool = false;
+/// Name of the standard input in the arguments and results
+pub const STDIN: &str = "-";
+
+/// A stream the strings are extracted from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// the standard input, read until it is closed
+    Stdin,
+    /// a regular file
+    File(PathBuf),
+}
+
+impl Input {
+    /// name of the input that is tagged on the results
+    pub fn name(&self) -> String {
+        match self {
+            Input::Stdin => STDIN.to_string(),
+            Input::File(p) => p.display().to_string(),
+        }
+    }
+
+    /// opens the input for reading
+    pub fn open(&self) -> Result<Box<dyn std::io::Read>, StringerError> {
+        match self {
+            Input::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            Input::File(p) => match std::fs::File::open(p) {
+                Ok(f) => Ok(Box::new(f)),
+                Err(e) => Err(StringerError::from(e).with_path(p)),
+            },
+        }
+    }
+}
+
+/// WalkOptions
+/// Decides which files are scanned when a directory
+/// is provided as input.
+#[derive(Default, Clone)]
+pub struct WalkOptions {
+    /// @include: only files matching one of the globs are scanned,
+    /// every file is scanned if empty
+    pub include: Vec<String>,
+    /// @exclude: files and directories matching one of the globs are skipped
+    pub exclude: Vec<String>,
+    /// @follow_symlinks: follows symbolic links, links are skipped otherwise
+    pub follow_symlinks: bool,
+}
+
+impl From<&crate::args::Args> for WalkOptions {
+    /// takes the directory walking options out of the arguments
+    fn from(value: &crate::args::Args) -> Self {
+        WalkOptions {
+            include: value.include.clone().unwrap_or_default(),
+            exclude: value.exclude.clone().unwrap_or_default(),
+            follow_symlinks: value.follow_symlinks.unwrap_or(FOLLOW_SYMLINKS),
+        }
+    }
+}
+
+/// compiles globs into a single set
+fn globs(patterns: &[String]) -> Result<globset::GlobSet, StringerError> {
+    let mut set = globset::GlobSetBuilder::new();
+    for p in patterns {
+        match globset::Glob::new(p) {
+            Ok(g) => {
+                set.add(g);
+            }
+            Err(e) => {
+                return Err(StringerError::Config(format!("invalid glob pattern {}: {}", p, e)));
+            }
+        };
+    }
+    set.build()
+        .map_err(|e| StringerError::Config(format!("unable to compile glob patterns: {}", e)))
+}
+
+/// Walks the given path recursively and returns the files that
+/// should be scanned in a stable order. Globs are matched against
+/// the path relative to the root, so `*.so` matches in every
+/// sub directory. A root that is a file is returned as is.
+/// Entries that cannot be read are returned as errors so the
+/// caller can report them and carry on.
+pub fn walk(
+    root: &Path,
+    opts: &WalkOptions,
+) -> Result<impl Iterator<Item = Result<PathBuf, StringerError>>, StringerError> {
+    let include = globs(&opts.include)?;
+    let exclude = globs(&opts.exclude)?;
+    let base = root.to_path_buf();
+    let relative = move |p: &Path| p.strip_prefix(&base).unwrap_or(p).to_path_buf();
+    let rel = relative.clone();
+
+    let files = walkdir::WalkDir::new(root)
+        .follow_links(opts.follow_symlinks)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(move |e| e.depth() == 0 || !exclude.is_match(rel(e.path())))
+        .filter_map(move |e| match e {
+            Ok(e) => {
+                if !e.file_type().is_file() {
+                    return None;
+                }
+                if e.depth() > 0 && !include.is_empty() &&
+                    !include.is_match(relative(e.path())) {
+                    return None;
+                }
+                Some(Ok(e.into_path()))
+            }
+            Err(e) => {
+                let path = e.path().map(Path::to_path_buf);
+                let source = match e.into_io_error() {
+                    Some(e) => e,
+                    // a symbolic link loop
+                    None => std::io::Error::other("file system loop"),
+                };
+                Some(Err(StringerError::Io { path, source }))
+            }
+        });
+
+    Ok(files)
+}
+
+/// checks if more than a single stream is going to be read from
+/// the inputs, in which case results should be tagged with their origin
+pub fn is_many(paths: &[String]) -> bool {
+    paths.len() > 1 || paths.iter().any(|p| p != STDIN && Path::new(p).is_dir())
+}
+
+/// Expands the inputs provided on the command line into the streams
+/// to read in order. `-` is the standard input and directories are
+/// walked with the provided options. No inputs means the standard input.
+pub fn expand(
+    paths: &[String],
+    opts: &WalkOptions,
+) -> Result<impl Iterator<Item = Result<Input, StringerError>>, StringerError> {
+    // invalid globs are reported before anything is read
+    globs(&opts.include)?;
+    globs(&opts.exclude)?;
+
+    let mut paths = paths.to_vec();
+    if paths.is_empty() {
+        paths.push(STDIN.to_string());
+    }
+
+    let opts = opts.clone();
+    Ok(paths.into_iter().flat_map(move |p| -> Box<dyn Iterator<Item = Result<Input, StringerError>>> {
+        if p == STDIN {
+            return Box::new(std::iter::once(Ok(Input::Stdin)));
+        }
+
+        let path = PathBuf::from(p);
+        if !path.is_dir() {
+            return Box::new(std::iter::once(Ok(Input::File(path))));
+        }
+        match walk(&path, &opts) {
+            Ok(files) => Box::new(files.map(|f| f.map(Input::File))),
+            Err(e) => Box::new(std::iter::once(Err(e))),
+        }
+    }))
+}
@@ -1,8 +1,21 @@
+pub mod archive;
 pub mod args;
+pub mod classify;
 pub mod config;
+pub mod decompress;
+pub mod elf;
+pub mod entropy;
 pub mod error;
+pub mod input;
+pub mod macho;
+pub mod parallel;
+pub mod pe;
+pub mod rules;
+pub mod section;
+pub mod table;
 pub mod writer;
 
+use std::borrow::Cow;
 use std::io::Read;
 use serde::ser::{Serialize, SerializeStruct};
 
@@ -10,23 +23,95 @@ use serde::ser::{Serialize, SerializeStruct};
 /// The main structure that is used to extract
 /// strings from a given stream according to the
 /// provided config.
-pub struct Stringer {
+/// The stream is pulled in chunks of `chunk_size` bytes so
+/// memory stays bounded by the chunk size plus the length
+/// of the string that is currently being read.
+/// Stringer is also an iterator over the extracted strings,
+/// reading errors end the iteration and are kept in `error`.
+pub struct Stringer<'a> {
     /// A custom configuration for the extractor
     config: config::StringerConfig,
-    /// input buffer where the stream is read and extracted
-    buffer: Vec<u8>,
-    /// the current byte in the stream
-    byte: u8,
+    /// class of every byte value under the configuration
+    table: table::ByteTable,
+    /// the stream where the strings are extracted from
+    input: Box<dyn Read + 'a>,
+    /// input buffer where the current chunk of the stream is kept,
+    /// or the whole input when it is scanned in place
+    buffer: Buffer<'a>,
+    /// the position in the stream of the first byte in the buffer
+    base: u64,
+    /// index of the cursor inside the buffer
+    cursor: usize,
+    /// the current character in the stream
+    ch: char,
     /// the position of the byte or the cursor
     pos: u64,
-    /// size of the input buffer
-    size: u64,
+    /// the position where the string that is being read started
+    start: Option<u64>,
+    /// number of characters read into the current string
+    count: u64,
+    /// the current string exceeded the maximum window size
+    overflow: bool,
+    /// the stream has no more bytes to be read
+    eof: bool,
+    /// where the stream comes from, tagged on each result
+    source: Option<String>,
+    /// compressions the stream was decoded from, outermost first
+    compression: Vec<decompress::Compression>,
+    /// number of archives the stream is a member of
+    depth: u64,
     /// checks if the buffer is at the end or not
     end: bool,
+    /// the error that ended the iteration over the strings
+    error: Option<error::StringerError>,
+    /// results of a string that yields several that are not returned yet
+    pending: std::collections::VecDeque<StringerResult>,
+    /// position in the stream after the last match of each byte pattern,
+    /// where the pattern is matched from in the next chunk
+    resume: Vec<u64>,
+    /// architectures of the slices of the input when none of them is
+    /// the architecture of the config
+    unmatched: Option<Vec<String>>,
     /// result of the extracted string
     pub results: Vec<StringerResult>,
 }
 
+/// Where the bytes that are scanned are kept
+enum Buffer<'a> {
+    /// chunks of a stream that are refilled while scanning
+    Chunks(Vec<u8>),
+    /// a slice that is scanned in place
+    Slice(&'a [u8]),
+    /// a memory mapped file that is scanned in place
+    Mapped(memmap2::Mmap),
+}
+
+impl std::ops::Deref for Buffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Buffer::Chunks(v) => v,
+            Buffer::Slice(s) => s,
+            Buffer::Mapped(m) => m,
+        }
+    }
+}
+
+impl Buffer<'_> {
+    /// the chunks that are refilled from the stream, a buffer that
+    /// is scanned in place is copied first, which a stream never needs
+    fn chunks(&mut self) -> &mut Vec<u8> {
+        if !matches!(self, Buffer::Chunks(_)) {
+            *self = Buffer::Chunks(self.to_vec());
+        }
+        match self {
+            Buffer::Chunks(v) => v,
+            _ => unreachable!(),
+        }
+    }
+}
+
 /// A result type where the extracted string and length of
 /// the string is stored if needed
 // #[derive(Serialize, Deserialize, Debug)]
@@ -36,19 +121,76 @@ pub struct StringerResult {
     string: std::ffi::CString,
     /// size of the string if needed
     length: Option<u64>,
+    /// Shannon entropy of the string in bits per character if needed
+    entropy: Option<f64>,
+    /// kinds of values found in the string if they are classified
+    kinds: Vec<classify::Kind>,
+    /// secret rules that flagged the string
+    findings: Vec<rules::Finding>,
+    /// ids of the regex patterns that matched the string if there are several
+    patterns: Vec<usize>,
+    /// encoding the string was found in before it was converted to UTF-8
+    encoding: config::Encoding,
+    /// the string is a match of the byte patterns with its raw bytes escaped
+    raw: bool,
+    /// position of the first byte of the string in the stream
+    offset: u64,
+    /// position right after the last byte of the string in the stream
+    end: u64,
+    /// radix the offset is displayed in if needed
+    radix: Option<config::Radix>,
+    /// path of the file the string is extracted from if needed
+    source: Option<String>,
+    /// section of the object file the string is in if needed
+    section: Option<String>,
+    /// virtual address of the string if its section is loaded in memory
+    address: Option<u64>,
+    /// architecture of the slice of a universal binary the string is in
+    slice: Option<String>,
+    /// compressions the input was decoded from, outermost first
+    compression: Vec<decompress::Compression>,
 }
 
 impl Serialize for StringerResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer {
-                let mut ss = serializer.serialize_struct("StringerResult", 2)?;
-                match self.length {
-                    Some(n) => {
-                        ss.serialize_field("length", &n)?;
-                    }
-                    _ => {}
-                };
+                let mut ss = serializer.serialize_struct("StringerResult", 14)?;
+                if let Some(ref s) = self.source {
+                    ss.serialize_field("source", s)?;
+                }
+                if !self.compression.is_empty() {
+                    let names: Vec<&str> = self.compression.iter().map(|c| c.name()).collect();
+                    ss.serialize_field("compression", &names)?;
+                }
+                ss.serialize_field("offset", &self.offset)?;
+                ss.serialize_field("end", &self.end)?;
+                if let Some(ref s) = self.slice {
+                    ss.serialize_field("slice", s)?;
+                }
+                if let Some(ref s) = self.section {
+                    ss.serialize_field("section", s)?;
+                }
+                if let Some(a) = self.address {
+                    ss.serialize_field("address", &a)?;
+                }
+                if let Some(n) = self.length {
+                    ss.serialize_field("length", &n)?;
+                }
+                if let Some(h) = self.entropy {
+                    ss.serialize_field("entropy", &h)?;
+                }
+                if !self.kinds.is_empty() {
+                    let names: Vec<&str> = self.kinds.iter().map(|k| k.name()).collect();
+                    ss.serialize_field("kinds", &names)?;
+                }
+                if !self.findings.is_empty() {
+                    ss.serialize_field("findings", &self.findings)?;
+                }
+                if !self.patterns.is_empty() {
+                    ss.serialize_field("patterns", &self.patterns)?;
+                }
+                ss.serialize_field("encoding", self.encoding_name())?;
                 ss.serialize_field("string", self.string.to_str().unwrap())?;
                 ss.end()
     }
@@ -56,202 +198,1636 @@ impl Serialize for StringerResult {
 
 impl std::fmt::Display for StringerResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.length {
-            Some(n) => {
-                let _ = f.write_fmt(format_args!("{}, ", n ));
+        if let Some(ref s) = self.source {
+            let _ = f.write_fmt(format_args!("{}: ", s));
+        }
+        if let Some(r) = self.radix {
+            let _ = f.write_fmt(format_args!("{} ", r.format(self.offset)));
+        }
+        match (&self.slice, &self.section) {
+            (Some(a), Some(s)) => {
+                let _ = f.write_fmt(format_args!("[{} {}] ", a, s));
             }
-            None => {}
-        };
+            (Some(s), None) | (None, Some(s)) => {
+                let _ = f.write_fmt(format_args!("[{}] ", s));
+            }
+            (None, None) => {}
+        }
+        if let Some(n) = self.length {
+            let _ = f.write_fmt(format_args!("{}, ", n ));
+        }
+        if let Some(h) = self.entropy {
+            let _ = f.write_fmt(format_args!("{:.2}, ", h));
+        }
+        if !self.kinds.is_empty() {
+            let names: Vec<&str> = self.kinds.iter().map(|k| k.name()).collect();
+            let _ = f.write_fmt(format_args!("({}) ", names.join(",")));
+        }
+        if !self.findings.is_empty() {
+            let findings: Vec<String> = self.findings.iter().map(|f| f.to_string()).collect();
+            let _ = f.write_fmt(format_args!("<{}> ", findings.join(",")));
+        }
+        if !self.patterns.is_empty() {
+            let ids: Vec<String> = self.patterns.iter().map(|p| p.to_string()).collect();
+            let _ = f.write_fmt(format_args!("{{{}}} ", ids.join(",")));
+        }
         f.write_fmt(format_args!("{}", self.string.to_str().unwrap()))
     }
 }
 
-impl<'a> Stringer {
-    /// creates a new stringer instance from objects that 
+impl StringerResult {
+    /// the string converted to UTF-8
+    pub fn as_str(&self) -> &str {
+        self.string.to_str().unwrap_or_default()
+    }
+
+    /// the string converted to UTF-8 as a C string
+    pub fn string(&self) -> &std::ffi::CStr {
+        &self.string
+    }
+
+    /// number of characters of the string if the config asks for it
+    pub fn length(&self) -> Option<u64> {
+        self.length
+    }
+
+    /// Shannon entropy of the string if the config asks for it
+    pub fn entropy(&self) -> Option<f64> {
+        self.entropy
+    }
+
+    /// kinds of values found in the string if they are classified
+    pub fn kinds(&self) -> &[classify::Kind] {
+        &self.kinds
+    }
+
+    /// secret rules that flagged the string
+    pub fn findings(&self) -> &[rules::Finding] {
+        &self.findings
+    }
+
+    /// ids of the regex patterns that matched the string if there are several
+    pub fn patterns(&self) -> &[usize] {
+        &self.patterns
+    }
+
+    /// encoding the string was found in
+    pub fn encoding(&self) -> config::Encoding {
+        self.encoding
+    }
+
+    /// checks if the string is a match of the byte patterns
+    /// on the raw bytes instead of a string in the encoding
+    pub fn raw(&self) -> bool {
+        self.raw
+    }
+
+    /// name of the encoding in the output, `raw` for the matches of the byte patterns
+    pub fn encoding_name(&self) -> &'static str {
+        match self.raw {
+            true => "raw",
+            false => self.encoding.name(),
+        }
+    }
+
+    /// position of the first byte of the string in the input
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// position right after the last byte of the string in the input
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// where the input comes from, as in `archive!member`
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// section of the object file the string is in
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    /// virtual address of the string if its section is loaded in memory
+    pub fn address(&self) -> Option<u64> {
+        self.address
+    }
+
+    /// architecture of the slice of a universal binary the string is in
+    pub fn slice(&self) -> Option<&str> {
+        self.slice.as_deref()
+    }
+
+    /// compressions the input was decoded from, outermost first
+    pub fn compression(&self) -> &[decompress::Compression] {
+        &self.compression
+    }
+}
+
+impl<'a> Stringer<'a> {
+    /// creates a new stringer instance from objects that
     /// implement the std::io::Read trait.
     /// Configuration is the default.
-    pub fn new<R: ?Sized>(input: &mut R) -> 
-        Result<Self, Box<dyn std::error::Error>> 
-        where R: Read
+    pub fn new<R>(input: &'a mut R) ->
+        Result<Self, error::StringerError>
+        where R: Read + ?Sized
     {
-        let mut buff: Vec<u8> = Vec::<u8>::new();
-        match input.read_to_end(&mut buff) {
-            Err(e) => {
-                return Err(Box::new(e));
-            }
-            _ => {}
-        };
+        Ok(Self::from_reader(input))
+    }
 
-        Ok(Stringer {
+    /// creates a new stringer instance that owns the provided reader.
+    /// Nothing is read until the strings are requested.
+    pub fn from_reader<R: Read + 'a>(input: R) -> Self {
+        let mut s = Stringer {
             config: config::StringerConfig::default(),
-            size: buff.len() as u64,
-            byte: 'blk: {
-                if buff.len() <= 0 {
-                    break 'blk 0;
-                }
-                break 'blk buff[0];
-            },
-            end: 'blk: {
-                if buff.len() == 0 {
-                    break 'blk true;
-                }
-                break 'blk false;
-            },
-            buffer: buff,
+            table: table::ByteTable::from_fn(|_| table::SKIP),
+            input: Box::new(input),
+            buffer: Buffer::Chunks(Vec::new()),
+            base: 0,
+            cursor: 0,
+            ch: '\0',
             pos: 0,
+            start: None,
+            count: 0,
+            overflow: false,
+            eof: false,
+            source: None,
+            compression: Vec::new(),
+            depth: 0,
+            end: false,
+            error: None,
+            pending: std::collections::VecDeque::new(),
+            resume: Vec::new(),
+            unmatched: None,
             results: Vec::new(),
-        })
+        };
+        s.table = s.build_table();
+        s
     }
 
-    pub fn set_config(self: &mut Self, config: crate::config::StringerConfig) {
+    /// creates a new stringer instance that scans a slice in place,
+    /// nothing is copied out of the slice while scanning.
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        let mut s = Self::from_reader(std::io::empty());
+        s.buffer = Buffer::Slice(data);
+        s.eof = true;
+        s
+    }
+
+    /// creates a new stringer instance for a file. Regular files are
+    /// memory mapped and scanned in place without copying them, while
+    /// pipes, devices and files that cannot be mapped are streamed in chunks.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, error::StringerError> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| error::StringerError::from(e).with_path(path))?;
+        let meta = file.metadata()
+            .map_err(|e| error::StringerError::from(e).with_path(path))?;
+
+        // an empty file cannot be mapped
+        if meta.is_file() && meta.len() > 0 {
+            // SAFETY: the map is only read. As with any memory mapped
+            // reader, a file truncated while it is scanned may fault.
+            if let Ok(map) = unsafe { memmap2::Mmap::map(&file) } {
+                #[cfg(unix)]
+                let _ = map.advise(memmap2::Advice::Sequential);
+
+                let mut s = Self::from_reader(std::io::empty());
+                s.buffer = Buffer::Mapped(map);
+                s.eof = true;
+                return Ok(s);
+            }
+        }
+        Ok(Self::from_reader(file))
+    }
+
+    pub fn set_config(&mut self, config: crate::config::StringerConfig) {
         self.config = config;
+        self.table = self.build_table();
+    }
+
+    /// classifies every byte value under the configuration.
+    /// Bytes above 0x7f start multibyte characters in UTF-8
+    /// and are never printable on their own in ASCII.
+    fn build_table(&self) -> table::ByteTable {
+        table::ByteTable::from_fn(|b| {
+            if b >= 0x80 {
+                return match self.config.encoding {
+                    config::Encoding::Utf8 => table::DECODE,
+                    _ => table::SKIP,
+                };
+            }
+            match self.printable(b as char) {
+                true => table::PRINT,
+                false => table::SKIP,
+            }
+        })
+    }
+
+    /// checks if runs of single byte characters can be scanned
+    /// with the byte table, which is not the case for UTF-16
+    fn fast(&self) -> bool {
+        self.config.table_scan && matches!(self.config.encoding, config::Encoding::Ascii | config::Encoding::Utf8)
+    }
+
+    /// Decodes the input while it is read if it is compressed, again for
+    /// every nested compression, and tags the results with the chain of
+    /// compressions. Nothing changes when decompression is disabled in
+    /// the config, so it must be called once the config is set.
+    /// Compressed files are streamed through the decoders instead of
+    /// being scanned in place. Inputs that start with a magic number but
+    /// whose first block is not decoded are scanned as they are.
+    pub fn decompressed(mut self) -> Result<Self, error::StringerError> {
+        if !self.config.decompress || self.pos != 0 ||
+            self.compression.len() >= decompress::MAX_DEPTH {
+            return Ok(self);
+        }
+
+        // a stream is read until the first blocks are in the buffer
+        while matches!(self.buffer, Buffer::Chunks(_)) &&
+            self.buffer.len() < decompress::PROBE_LEN && !self.eof {
+            self.fill()?;
+        }
+        let Some(c) = decompress::Compression::detect(&self.buffer) else {
+            return Ok(self);
+        };
+        // the raw bytes are scanned when they are not decoded
+        let head = &self.buffer[..self.buffer.len().min(decompress::PROBE_LEN)];
+        let whole = self.eof && head.len() == self.buffer.len();
+        if !c.probe(head, whole) {
+            return Ok(self);
+        }
+
+        let config = self.config.clone();
+        let source = self.source.take();
+        let mut compression = std::mem::take(&mut self.compression);
+        compression.push(c);
+        let depth = self.depth;
+
+        let mut s = Self::from_reader(c.decoder(self.into_reader())?);
+        s.set_config(config);
+        s.source = source;
+        s.compression = compression;
+        s.depth = depth;
+        s.decompressed()
+    }
+
+    /// the stream as a reader from its start, the bytes
+    /// that are already in the buffer come first
+    fn into_reader(self) -> Box<dyn Read + 'a> {
+        match self.buffer {
+            Buffer::Chunks(v) => Box::new(std::io::Cursor::new(v).chain(self.input)),
+            Buffer::Slice(s) => Box::new(s),
+            Buffer::Mapped(m) => Box::new(std::io::Cursor::new(m)),
+        }
     }
 
-    /// Advances the cursor by one step
-    /// If end is there then the end flag is set on
-    fn advance(self: &mut Self) {
-        if self.pos >= self.size - 1 {
-            self.end = true;
-            return;
+    /// reads the rest of a stream into the buffer
+    fn read_to_end(&mut self) -> std::io::Result<()> {
+        if !self.eof {
+            let buffer = self.buffer.chunks();
+            self.input.read_to_end(buffer)?;
+            self.eof = true;
+        }
+        Ok(())
+    }
+
+    /// Detects if the input is an archive whose members should be scanned
+    /// one by one with `read_members`. Archives nested deeper than the
+    /// archive depth of the config are not detected and are scanned as
+    /// they are, so it must be called once the config is set.
+    /// Inputs that start with a magic number but whose first header or
+    /// central directory is not parsed are scanned as they are.
+    pub fn archive(&mut self) -> Result<Option<archive::Archive>, error::StringerError> {
+        if self.depth >= self.config.archive_depth || self.pos != 0 {
+            return Ok(None);
+        }
+
+        // a stream is read until the first header is in the buffer
+        while matches!(self.buffer, Buffer::Chunks(_)) &&
+            self.buffer.len() < archive::PROBE_LEN && !self.eof {
+            self.fill()?;
         }
+        let Some(kind) = archive::Archive::detect(&self.buffer) else {
+            return Ok(None);
+        };
+        // the central directory of a zip archive is at its end
+        if kind == archive::Archive::Zip {
+            self.read_to_end()?;
+        }
+        // the raw bytes are scanned when they are not an archive
+        Ok(Some(kind).filter(|k| k.probe(&self.buffer, self.eof)))
+    }
+
+    /// Passes every file of an archive to `f` with its source, as in
+    /// `archive!member/path`, and a stringer over its contents that has
+    /// the config of the archive. The results of the member are tagged with
+    /// that source and with the compressions of the archive.
+    /// Zip archives are read into memory unless they are scanned in place,
+    /// tar and ar archives are streamed member by member.
+    pub fn read_members<F>(mut self, kind: archive::Archive, mut f: F) -> Result<(), error::StringerError>
+    where
+        F: FnMut(&str, Stringer<'_>) -> Result<(), error::StringerError>,
+    {
+        let parent = self.source.take().unwrap_or_default();
+        let config = self.config.clone();
+        let compression = self.compression.clone();
+        let depth = self.depth + 1;
+        let mut member = |path: &str, r: &mut dyn Read| {
+            let source = format!("{}{}{}", parent, archive::SEPARATOR, path);
+            let mut s = Stringer::from_reader(r);
+            s.set_config(config.clone());
+            s.source = Some(source.clone());
+            s.compression = compression.clone();
+            s.depth = depth;
+            f(&source, s)
+        };
+
+        match kind {
+            archive::Archive::Zip => {
+                self.read_to_end()?;
+                archive::zip(&self.buffer, &mut member)
+            }
+            archive::Archive::Tar => archive::tar(self.into_reader(), &mut member),
+            archive::Archive::Ar => archive::ar(self.into_reader(), &mut member),
+        }
+    }
+
+    /// tags every extracted string with where the stream comes from
+    pub fn set_source(&mut self, source: String) {
+        self.source = Some(source);
+    }
+
+    /// Pulls the next chunk of the stream into the buffer.
+    /// Bytes that were already consumed are dropped except for
+    /// the string that is currently being read, which is carried
+    /// over so it can continue across the chunk boundary.
+    fn fill(&mut self) -> std::io::Result<()> {
+        let keep = match self.start {
+            Some(s) if !self.overflow => (s - self.base) as usize,
+            _ => self.cursor,
+        };
+        let buffer = self.buffer.chunks();
+        buffer.drain(..keep);
+        self.base += keep as u64;
+        self.cursor -= keep;
+
+        let len = buffer.len();
+        buffer.resize(len + self.config.chunk_size.max(1) as usize, 0);
+        let n = loop {
+            match self.input.read(&mut buffer[len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    buffer.truncate(len);
+                    return Err(e);
+                }
+            }
+        };
+        buffer.truncate(len + n);
+        if n == 0 {
+            self.eof = true;
+        }
+        Ok(())
+    }
 
-        self.pos += 1;
-        self.byte = self.buffer[self.pos as usize];
+    /// Advances the cursor by the given number of bytes
+    fn advance(&mut self, n: usize) {
+        self.cursor += n;
+        self.pos += n as u64;
+    }
+
+    /// the maximum number of bytes that are looked at to read a
+    /// single character in the configured encoding
+    fn width(&self) -> usize {
+        match self.config.encoding {
+            config::Encoding::Ascii => 1,
+            config::Encoding::Utf8 => 4,
+            // a surrogate pair takes two 16-bit code units, and the
+            // misalignment check looks at the two characters after
+            config::Encoding::Utf16Le |
+            config::Encoding::Utf16Be => 6,
+        }
+    }
+
+    /// decodes the character at the given index of the buffer and
+    /// returns it with the number of bytes it takes.
+    /// Returns None if the bytes are not a valid character.
+    fn decode(&self, at: usize) -> Option<(char, usize)> {
+        let b = &self.buffer[at..];
+        match self.config.encoding {
+            config::Encoding::Ascii => Some((*b.first()? as char, 1)),
+            config::Encoding::Utf8 => {
+                let n = match *b.first()? {
+                    0x00..=0x7f => 1,
+                    0xc2..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    0xf0..=0xf4 => 4,
+                    _ => return None,
+                };
+                let c = std::str::from_utf8(b.get(..n)?).ok()?.chars().next()?;
+                Some((c, n))
+            }
+            config::Encoding::Utf16Le | config::Encoding::Utf16Be => {
+                let unit = |i: usize| -> Option<u16> {
+                    let u = b.get(i..i + 2)?;
+                    match self.config.encoding {
+                        config::Encoding::Utf16Le => Some(u16::from_le_bytes([u[0], u[1]])),
+                        _ => Some(u16::from_be_bytes([u[0], u[1]])),
+                    }
+                };
+                let hi = unit(0)?;
+                if (0xd800..0xdc00).contains(&hi) {
+                    let c = char::decode_utf16([hi, unit(2)?]).next()?.ok()?;
+                    return Some((c, 4));
+                }
+                let c = char::decode_utf16([hi]).next()?.ok()?;
+                Some((c, 2))
+            }
+        }
+    }
+
+    /// checks if two printable ASCII characters start on the next byte.
+    /// A UTF-16 string that is preceded by an odd number of bytes would
+    /// otherwise be read misaligned as a run of unrelated characters,
+    /// while one such character is also how text ends before a NUL.
+    fn misaligned(&self) -> bool {
+        let ascii = |at| matches!(self.decode(at), Some((c, _)) if c.is_ascii() && self.printable(c));
+        ascii(self.cursor + 1) && ascii(self.cursor + 3)
     }
 
     /// This function is responsible for chekcing
-    /// if the current byte is acceptable or not according
+    /// if the current character is acceptable or not according
     /// to the rule provided by the configuration.
-    fn should_read(self: &Self) -> bool {
-        if  self.byte.is_ascii_alphanumeric() {
+    fn should_read(&self) -> bool {
+        self.printable(self.ch)
+    }
+
+    /// checks if a character is printable according to the configuration
+    fn printable(&self, c: char) -> bool {
+        if !c.is_ascii() {
+            return self.config.encoding != config::Encoding::Ascii &&
+                self.printable_unicode(c);
+        }
+
+        if  c.is_ascii_alphanumeric() {
                 return true;
         }
 
-        if self.config.special &&  
-            self.byte.is_ascii_punctuation() {
+        if self.config.special &&
+            c.is_ascii_punctuation() {
                 return true;
         }
 
-        if self.config.whitespace_include && 
-            (self.byte == 0x20 || 
-             self.byte == 0x09 || 
-             self.byte == 0x0b
-             ) 
+        if self.config.whitespace_include &&
+            (c == ' ' ||
+             c == '\t' ||
+             c == '\x0b'
+             )
         {
                 return true;
         }
 
-        if self.config.line_include && (self.byte == 0x0a || self.byte == 0x0d) {
+        if self.config.line_include && (c == '\n' || c == '\r') {
             return true;
         }
 
-        return false;
+        false
     }
 
-    /// reads in and returns a buffer if the config condition
-    /// is met and not the end is reached
-    fn read(self: &mut Self) -> Option<Vec<u8>> {
-        while !self.should_read() {
-            self.advance();
-            if self.end {
-                return None;
+    /// checks a non ASCII character against the unicode
+    /// categories that are enabled in the configuration
+    fn printable_unicode(&self, c: char) -> bool {
+        use unicode_general_category::GeneralCategory as G;
+
+        let cats = &self.config.categories;
+        let utf16 = matches!(self.config.encoding,
+            config::Encoding::Utf16Le | config::Encoding::Utf16Be);
+        if utf16 && !self.config.utf16_unicode {
+            return false;
+        }
+        // ASCII text read as UTF-16 is a run of code units made of two
+        // printable ASCII bytes, mostly ideographs only read on request
+        let ascii = |b: u32| (0x20..0x7f).contains(&b);
+        if utf16 && !self.config.utf16_cjk && ascii(u32::from(c) >> 8) && ascii(u32::from(c) & 0xff) {
+            return false;
+        }
+        match unicode_general_category::get_general_category(c) {
+            G::UppercaseLetter | G::LowercaseLetter | G::TitlecaseLetter |
+            G::ModifierLetter | G::OtherLetter => cats.letters,
+            G::NonspacingMark | G::SpacingMark | G::EnclosingMark => cats.marks,
+            G::DecimalNumber | G::LetterNumber | G::OtherNumber => cats.numbers,
+            G::MathSymbol | G::CurrencySymbol | G::ModifierSymbol |
+            G::OtherSymbol => cats.symbols,
+            G::ConnectorPunctuation | G::DashPunctuation | G::OpenPunctuation |
+            G::ClosePunctuation | G::InitialPunctuation | G::FinalPunctuation |
+            G::OtherPunctuation => self.config.special,
+            G::SpaceSeparator => self.config.whitespace_include,
+            G::LineSeparator | G::ParagraphSeparator => self.config.line_include,
+            // zero width joiner keeps emoji sequences together
+            G::Format => cats.symbols && c == '\u{200d}',
+            _ => false,
+        }
+    }
+
+    /// ends the string that is currently being read and returns
+    /// its starting and ending position if the window size conditions
+    /// of the config are met
+    fn read(&mut self) -> Option<(u64, u64)> {
+        let start = self.start.take()?;
+        let count = self.count;
+        self.count = 0;
+        if std::mem::take(&mut self.overflow) {
+            return None;
+        }
+
+        // window sizes are counted in characters
+        if count < self.config.window_min_size {
+            return None;
+        }
+
+        Some((start, self.pos))
+    }
+
+    /// the bytes of the stream between two positions,
+    /// they must still be in the buffer
+    fn bytes(&self, start: u64, end: u64) -> &[u8] {
+        &self.buffer[(start - self.base) as usize..(end - self.base) as usize]
+    }
+
+    /// checks a string against the filters of the config
+    fn matches(&self, s: &str) -> bool {
+        self.patterns(s).is_some() && self.keeps(s)
+    }
+
+    /// checks a string against the filters of the config besides the patterns
+    fn keeps(&self, s: &str) -> bool {
+        self.passes(s) && self.selects(&self.classify(s), &self.findings(s))
+    }
+
+    /// Finds the pieces of a string that the extraction mode takes out of
+    /// it, the matches of the patterns or their named groups. The pieces
+    /// are byte ranges of the string in string order, with the ids of the
+    /// patterns that found them when there are several patterns. A piece
+    /// found by several patterns is taken once, empty ones are skipped.
+    fn pieces(&self, s: &str, extract: &config::Extract) -> Vec<(usize, usize, Vec<usize>)> {
+        let regexes = self.config.pattern_regexes();
+        let found = regexes.iter().enumerate().flat_map(|(id, re)| {
+            re.captures_iter(s).flat_map(move |c| {
+                let found: Vec<regex::Match> = match extract {
+                    config::Extract::Matches => c.get(0).into_iter().collect(),
+                    config::Extract::Groups(names) => names.iter().filter_map(|n| c.name(n)).collect(),
+                };
+                found.into_iter().filter(|m| !m.is_empty()).map(move |m| (m.start(), m.end(), id))
+            })
+        });
+        keyed_pieces(found, regexes.len() > 1)
+    }
+
+    /// Finds the matches of the byte patterns of the config in raw
+    /// bytes, as byte ranges in offset order with the ids of the patterns
+    /// that found them when there are several patterns. The pattern `id`
+    /// is matched from `from[id]` on, or from the start, and only the
+    /// matches that start before `limit` are taken. A range found by
+    /// several patterns is taken once, empty matches are skipped.
+    fn byte_pieces(&self, data: &[u8], from: &[usize], limit: usize) -> Vec<(usize, usize, Vec<usize>)> {
+        let regexes = &self.config.byte_regexes;
+        let found = |id: usize, re| {
+            find_from(re, data, from.get(id).copied().unwrap_or(0))
+                .take_while(move |m| m.start() < limit)
+                .filter(|m| !m.is_empty())
+        };
+        if let [re] = &regexes[..] {
+            // the matches of a single pattern are in order and never the same
+            return found(0, re).map(|m| (m.start(), m.end(), Vec::new())).collect();
+        }
+        let found = regexes.iter().enumerate().flat_map(|(id, re)| {
+            found(id, re).map(move |m| (m.start(), m.end(), id))
+        });
+        keyed_pieces(found, true)
+    }
+
+    /// Returns the matches of the byte patterns in raw bytes that meet
+    /// the other filters of the config, `offset` is the position of the
+    /// bytes in the stream. The window sizes do not apply to the matches.
+    fn byte_matches(&self, data: &[u8], offset: u64) -> Vec<StringerResult> {
+        self.byte_results(data, offset, self.byte_pieces(data, &[], data.len()))
+    }
+
+    /// the results of the matches of the byte patterns in raw bytes
+    /// that meet the other filters of the config, labelled as raw bytes
+    fn byte_results(&self, data: &[u8], offset: u64, pieces: Vec<(usize, usize, Vec<usize>)>) -> Vec<StringerResult> {
+        pieces
+            .into_iter()
+            .filter_map(|(start, end, patterns)| {
+                let string = std::ffi::CString::new(escape(&data[start..end])).ok()?;
+                let r = self.to_result(string, offset + start as u64, offset + end as u64, patterns)?;
+                // the length of a match is its bytes, not the characters of the escaped text
+                let length = r.length.map(|_| (end - start) as u64);
+                Some(StringerResult { raw: true, length, ..r })
+            })
+            .collect()
+    }
+
+    /// Matches the byte patterns against the buffer after reading the next
+    /// chunks of a stream, so a stream is never read into memory whole.
+    /// The matches that start in the last match overlap of the config
+    /// bytes are left for the next chunks where they may go on, a longer
+    /// match may still be cut at the end of the buffer. Each pattern goes on after its
+    /// last match so the matches of a pattern do not overlap.
+    fn match_chunk(&mut self) -> Result<(), error::StringerError> {
+        let overlap = self.config.match_overlap.max(1) as usize;
+        while !self.eof && self.buffer.len() - self.cursor < 2 * overlap {
+            self.fill()?;
+        }
+        let at = self.base + self.cursor as u64;
+        let data = &self.buffer[self.cursor..];
+        let limit = match self.eof {
+            true => data.len(),
+            false => data.len() - overlap,
+        };
+        let from: Vec<usize> = self.resume.iter().map(|&r| (r - at) as usize).collect();
+        let pieces = self.byte_pieces(data, &from, limit);
+
+        // every match that starts before the limit is taken
+        let mut resume = vec![at + limit as u64; self.config.byte_regexes.len()];
+        for (r, &last) in resume.iter_mut().zip(&self.resume) {
+            *r = (*r).max(last);
+        }
+        for (_, end, ids) in &pieces {
+            let ids = if ids.is_empty() { &[0][..] } else { ids };
+            for &id in ids {
+                resume[id] = resume[id].max(at + *end as u64);
             }
         }
+        let results = self.byte_results(data, at, pieces);
+        self.pending.extend(results);
+        self.resume = resume;
+
+        if self.eof {
+            self.skip_to_end();
+            return Ok(());
+        }
+        // the byte before is kept for assertions such as word boundaries
+        let keep = self.resume.iter().min().map_or(at, |&r| r.saturating_sub(1).max(at));
+        self.cursor = (keep - self.base) as usize;
+        self.pos = keep;
+        Ok(())
+    }
+
+    /// Matches a string against every regex pattern of the config in a
+    /// single pass. Returns the ids of the patterns that match, which are
+    /// only kept when there are several patterns, or None if none matches.
+    fn patterns(&self, s: &str) -> Option<Vec<usize>> {
+        let Some(set) = self.config.pattern_set() else {
+            return Some(Vec::new());
+        };
+        if set.len() == 1 {
+            return set.is_match(s).then(Vec::new);
+        }
+        let ids: Vec<usize> = set.matches(s).into_iter().collect();
+        (!ids.is_empty()).then_some(ids)
+    }
+
+    /// the kinds of values in a string, which are only
+    /// looked for when the config tags or filters them
+    fn classify(&self, s: &str) -> Vec<classify::Kind> {
+        match self.config.classified() {
+            true => classify::classify(s),
+            false => Vec::new(),
+        }
+    }
+
+    /// the findings of the secret rules of the config in a string
+    fn findings(&self, s: &str) -> Vec<rules::Finding> {
+        rules::check(&self.config.rules, s)
+    }
+
+    /// Checks the kinds and the findings of a string against the config.
+    /// A string is kept if any of its kinds is selected, and only
+    /// if it has findings when the config asks for those only.
+    fn selects(&self, kinds: &[classify::Kind], findings: &[rules::Finding]) -> bool {
+        if !self.config.kinds.is_empty() && !kinds.iter().any(|k| self.config.kinds.contains(k)) {
+            return false;
+        }
+        !self.config.findings_only || !findings.is_empty()
+    }
 
-        let mut buff: Vec<u8> = Vec::<u8>::new();
+    /// Checks a string against the exclude patterns and the entropy range
+    /// of the config, the entropy is only computed for a range. Extracted
+    /// pieces are checked on their own.
+    fn passes(&self, s: &str) -> bool {
+        if self.config.exclude_regex.as_ref().is_some_and(|r| r.is_match(s)) {
+            return false;
+        }
+        if self.config.entropy_min.is_none() && self.config.entropy_max.is_none() {
+            return true;
+        }
+        let h = entropy::shannon(s);
+        self.config.entropy_min.is_none_or(|m| h >= m) &&
+            self.config.entropy_max.is_none_or(|m| h <= m)
+    }
 
-        if self.config.split == 0 {
-            loop {
-                if !self.should_read() {
-                    break;
+    /// returns a stringer result type after reading
+    /// based on the rule the string size is also returned if needed
+    fn to_stringer(&self, buff: Vec<u8>, offset: u64, end: u64) -> Option<StringerResult> {
+        let string =
+            std::ffi::CString::from_vec_with_nul(buff).ok()?;
+        let patterns = self.patterns(string.to_str().ok()?)?;
+        self.to_result(string, offset, end, patterns)
+    }
+
+    /// Returns the results of a string found in an encoding: the string
+    /// itself, or in an extraction mode the pieces taken out of it, each
+    /// with the positions of its own bytes in the stream.
+    fn to_results(&self, buff: Vec<u8>, offset: u64, end: u64, encoding: config::Encoding) -> Vec<StringerResult> {
+        let Some(ref extract) = self.config.extract else {
+            return self.to_stringer(buff, offset, end).into_iter().collect();
+        };
+        let Ok(s) = std::str::from_utf8(&buff[..buff.len() - 1]) else {
+            return Vec::new();
+        };
+
+        self.pieces(s, extract)
+            .into_iter()
+            .filter_map(|(start, stop, patterns)| {
+                let offset = offset + encoding.encoded_len(&s[..start]);
+                let end = offset + encoding.encoded_len(&s[start..stop]);
+                let string = std::ffi::CString::new(&s[start..stop]).ok()?;
+                self.to_result(string, offset, end, patterns)
+            })
+            .collect()
+    }
+
+    /// returns the result of a string that the patterns matched,
+    /// if it meets the other filters of the config
+    fn to_result(
+        &self,
+        string: std::ffi::CString,
+        offset: u64,
+        end: u64,
+        patterns: Vec<usize>,
+    ) -> Option<StringerResult> {
+        let s = string.to_str().ok()?;
+        if !self.passes(s) {
+            return None;
+        }
+        let kinds = self.classify(s);
+        let findings = self.findings(s);
+        if !self.selects(&kinds, &findings) {
+            return None;
+        }
+
+        Some(StringerResult {
+            length: match self.config.length {
+                true => { Some(s.chars().count() as u64) },
+                false => { None }
+            },
+            entropy: match self.config.entropy {
+                true => Some(entropy::round(entropy::shannon(s))),
+                false => None,
+            },
+            kinds,
+            findings,
+            patterns,
+            encoding: self.config.encoding,
+            raw: false,
+            offset,
+            end,
+            radix: self.config.radix,
+            source: self.source.clone(),
+            section: None,
+            address: None,
+            slice: None,
+            compression: self.compression.clone(),
+            string,
+        })
+    }
+
+    /// reads the stream until the next string is extracted.
+    /// Returns None once the end of the stream is reached.
+    pub fn read_next(&mut self) ->
+        Result<Option<StringerResult>, error::StringerError>
+    {
+        if let Some(r) = self.pending.pop_front() {
+            return Ok(Some(r));
+        }
+        // byte patterns run over the input a chunk at a time
+        if self.config.raw() {
+            while self.pending.is_empty() && !self.end {
+                self.match_chunk()?;
+            }
+            return Ok(self.pending.pop_front());
+        }
+        while let Some((start, end)) = self.next_range()? {
+            let mut buff = self.config.encoding.to_utf8(self.bytes(start, end));
+            buff.push(0);
+            let mut results = self.to_results(buff, start, end, self.config.encoding).into_iter();
+            if let Some(r) = results.next() {
+                self.pending.extend(results);
+                return Ok(Some(r));
+            }
+        }
+        Ok(None)
+    }
+
+    /// reads the stream until the next string that meets the window
+    /// size conditions and returns where it starts and ends.
+    /// The bytes of the string are in the buffer until the next call.
+    fn next_range(&mut self) -> Result<Option<(u64, u64)>, error::StringerError> {
+        while !self.end {
+            if self.cursor >= self.buffer.len() {
+                if !self.eof {
+                    self.fill()?;
+                    continue;
                 }
 
-                if self.end {
-                    break;
+                self.end = true;
+                return Ok(self.read());
+            }
+
+            // a multibyte character may continue in the next chunk
+            if self.buffer.len() - self.cursor < self.width() && !self.eof {
+                self.fill()?;
+                continue;
+            }
+
+            // whole runs of single byte characters are skipped or
+            // read at once, the rest is decoded one character at a time
+            if self.fast() {
+                let avail = &self.buffer[self.cursor..];
+                if self.start.is_none() {
+                    let n = self.table.span(avail, table::SKIP);
+                    if n > 0 {
+                        self.advance(n);
+                        continue;
+                    }
                 }
 
-                buff.push(self.byte);
-                self.advance();
+                let mut n = self.table.span(avail, table::PRINT) as u64;
+                if n > 0 {
+                    if self.config.split != 0 {
+                        n = n.min(self.config.split - self.count);
+                    }
+                    if self.start.is_none() {
+                        self.start = Some(self.pos);
+                    }
+                    self.count += n;
+                    if self.config.window_max_size != 0 &&
+                       self.count > self.config.window_max_size {
+                        self.overflow = true;
+                    }
+                    self.advance(n as usize);
+
+                    if self.config.split != 0 && self.count == self.config.split {
+                        let res = self.read();
+                        if res.is_some() {
+                            return Ok(res);
+                        }
+                    }
+                    continue;
+                }
             }
-        } else {
-            for _ in 0..self.config.split {
-                if !self.should_read() || self.end {
-                    break;
+
+            // invalid sequences are skipped one byte at a time
+            let (ch, n) = self.decode(self.cursor).unwrap_or(('\0', 1));
+            self.ch = ch;
+            let utf16 = matches!(self.config.encoding,
+                config::Encoding::Utf16Le | config::Encoding::Utf16Be);
+            if !self.should_read() ||
+                (utf16 && !ch.is_ascii() && self.misaligned()) {
+                // a UTF-16 string ends on a whole code unit that is passed
+                // over, so the next string is read in the same alignment
+                let step = match utf16 && self.start.is_some() && n >= 2 && !self.should_read() {
+                    true => 2,
+                    false => 1,
+                };
+                let res = self.read();
+                self.advance(step);
+                if res.is_some() {
+                    return Ok(res);
                 }
+                continue;
+            }
+
+            if self.start.is_none() {
+                self.start = Some(self.pos);
+            }
+            self.count += 1;
+            // maximum window size limitation, the rest of the string
+            // is skipped without keeping it in the buffer
+            if self.config.window_max_size != 0 &&
+               self.count > self.config.window_max_size {
+                self.overflow = true;
+            }
+            self.advance(n);
 
-                buff.push(self.byte);
-                self.advance();
+            if self.config.split != 0 && self.count == self.config.split {
+                let res = self.read();
+                if res.is_some() {
+                    return Ok(res);
+                }
             }
         }
 
-        if buff.len() < self.config.window_min_size as usize {
-            return None;
+        Ok(None)
+    }
+
+    /// reads all the available strings in the stream
+    /// and stores them in results
+    pub fn read_strings(&mut self) -> Result<(), error::StringerError> {
+        while let Some(r) = self.read_next()? {
+            self.results.push(r);
         }
+        Ok(())
+    }
 
-        // maximum window size limitation
-        if self.config.window_max_size != 0 && 
-           buff.len() as u64 > self.config.window_max_size {
-            return None;
+    /// reads all the available strings like `read_strings`, scanning
+    /// on the number of threads of the config. Only inputs that are
+    /// scanned in place, slices and mapped files, are split across
+    /// threads while streams are read sequentially.
+    /// The results are the same as the sequential ones, in offset order.
+    /// Inputs matched by byte patterns are always read sequentially.
+    pub fn read_strings_parallel(&mut self) -> Result<(), error::StringerError> {
+        let threads = self.threads();
+        if threads == 1 || self.config.raw() || matches!(self.buffer, Buffer::Chunks(_)) || self.pos != 0 {
+            return self.read_strings();
+        }
+
+        let results = parallel::scan(&self.buffer, &self.config, self.source.clone(), threads);
+        self.results.extend(results);
+        self.skip_to_end();
+        Ok(())
+    }
+
+    /// reads all the available strings like `read_strings_parallel`,
+    /// limited to the sections of object files and the slices of universal
+    /// binaries that are selected in the config. Each result is tagged
+    /// with its slice, section and address. Strings of sections that are
+    /// inside a selected resource string are only reported as the latter.
+    /// Inputs that are not object files are read whole like with
+    /// `read_strings_parallel`, the streams of object files are read into
+    /// memory first since the headers must be parsed.
+    pub fn read_strings_sections(&mut self) -> Result<(), error::StringerError> {
+        if !self.sectioned()? {
+            return self.read_strings_parallel();
+        }
+        self.read_to_end()?;
+
+        let arch = self.config.arch.as_deref();
+        if let Some(a) = arch.filter(|a| *a != macho::ALL) {
+            let arches = macho::arches(&self.buffer);
+            if !arches.is_empty() && !arches.iter().any(|n| n == a) {
+                self.unmatched = Some(arches);
+            }
+        }
+        let Some(sections) = section::select(&self.buffer, &self.config.sections, arch) else {
+            return self.read_strings_parallel();
+        };
+
+        // the strings of sections such as .rsrc that are in a selected
+        // resource string are left out so each string is reported once,
+        // the resource strings are in file order and do not overlap
+        let strings: Vec<(u64, u64)> = sections
+            .iter()
+            .filter(|s| s.encoding.is_some())
+            .map(|s| (s.offset, s.offset + s.size))
+            .collect();
+        let in_string = |r: &StringerResult| {
+            let i = strings.partition_point(|&(start, _)| start <= r.offset);
+            i > 0 && r.end <= strings[i - 1].1
+        };
+
+        let threads = self.threads();
+        let compression = self.compression.clone();
+        for s in &sections {
+            // byte patterns match the raw bytes of single string sections too
+            if s.encoding.is_some() && !self.config.raw() {
+                let results = self.whole_string(s);
+                self.results.extend(results);
+                continue;
+            }
+
+            let data = &self.buffer[s.offset as usize..(s.offset + s.size) as usize];
+            let results = match self.config.raw() {
+                true => self.byte_matches(data, 0),
+                false => parallel::scan(data, &self.config, self.source.clone(), threads),
+            };
+            // offsets are positions in the whole input
+            let results = results.into_iter().map(|mut r| {
+                r.address = s.address.map(|a| a + r.offset);
+                r.offset += s.offset;
+                r.end += s.offset;
+                r.section = (!s.name.is_empty()).then(|| s.name.clone());
+                r.slice = s.slice.clone();
+                r.compression = compression.clone();
+                r
+            });
+            self.results.extend(results.filter(|r| s.encoding.is_some() || !in_string(r)));
+        }
+        self.skip_to_end();
+        Ok(())
+    }
+
+    /// Checks if the config selects sections or slices and the input is an
+    /// object file they may be selected in. A stream is only read until
+    /// its magic number, so other inputs can still be streamed as usual.
+    pub fn sectioned(&mut self) -> Result<bool, error::StringerError> {
+        if !self.config.sectioned() || self.pos != 0 {
+            return Ok(false);
+        }
+
+        // a stream is read until the magic number is in the buffer
+        while matches!(self.buffer, Buffer::Chunks(_)) &&
+            self.buffer.len() < section::MAGIC_LEN && !self.eof {
+            self.fill()?;
+        }
+        Ok(section::is_object(&self.buffer, &self.config.sections))
+    }
+
+    /// Returns a section that is a single string, such as a resource
+    /// string, as results if it meets the window size conditions and
+    /// the filters of the config. Its characters are taken as they are.
+    fn whole_string(&self, s: &section::Section) -> Vec<StringerResult> {
+        let Some(encoding) = s.encoding else {
+            return Vec::new();
+        };
+        let mut buff = encoding.to_utf8(&self.buffer[s.offset as usize..(s.offset + s.size) as usize]);
+
+        let count = std::str::from_utf8(&buff).map_or(0, |b| b.chars().count() as u64);
+        if count < self.config.window_min_size ||
+            (self.config.window_max_size != 0 && count > self.config.window_max_size) {
+            return Vec::new();
         }
 
         buff.push(0);
-        Some(buff)
+        let mut results = self.to_results(buff, s.offset, s.offset + s.size, encoding);
+        for r in results.iter_mut() {
+            r.encoding = encoding;
+            r.section = Some(s.name.clone());
+            r.address = s.address.map(|a| a + r.offset - s.offset);
+            r.slice = s.slice.clone();
+        }
+        results
     }
 
-    /// returns a stringer result type after reading
-    /// based on the rule the string size is also returned if needed
-    fn to_stringer(self: &mut Self, buff: Vec<u8>) -> Option<StringerResult> {
-        let len = buff.len() as u64;
-        let string = 
-            std::ffi::CString::from_vec_with_nul(buff);
-
-        match string {
-            Err(_) => { return None; }
-            Ok(s)  => {
-                return Some(StringerResult {
-                    length: match self.config.length {
-                        true => { Some(len - 1) },
-                        false => { None }
-                    },
-                    string: s
+    /// the number of threads scanning an input, 0 in the config uses every core
+    fn threads(&self) -> usize {
+        match self.config.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n as usize,
+        }
+    }
+
+    /// moves past the end of an input whose strings are all read
+    fn skip_to_end(&mut self) {
+        self.pos = self.base + self.buffer.len() as u64;
+        self.cursor = self.buffer.len();
+        self.end = true;
+    }
+
+    /// the architectures of the slices of the input when none of them
+    /// is the architecture of the config, so nothing was scanned
+    pub fn unmatched_arch(&self) -> Option<&[String]> {
+        self.unmatched.as_deref()
+    }
+
+    /// returns the error that ended the iteration, if any
+    pub fn take_error(&mut self) -> Option<error::StringerError> {
+        self.error.take()
+    }
+}
+
+/// Takes the pieces found by the patterns, as ranges with the id of
+/// the pattern, in order and each range once. The ranges are keyed so
+/// that a range found by several patterns gets all of their ids, which
+/// are only kept when there are several patterns.
+fn keyed_pieces<I>(found: I, several: bool) -> Vec<(usize, usize, Vec<usize>)>
+where
+    I: Iterator<Item = (usize, usize, usize)>,
+{
+    if !several {
+        // a single pattern only finds a range again in another named group
+        let mut pieces: Vec<(usize, usize, Vec<usize>)> = found.map(|(s, e, _)| (s, e, Vec::new())).collect();
+        pieces.sort_by_key(|p| (p.0, p.1));
+        pieces.dedup_by_key(|p| (p.0, p.1));
+        return pieces;
+    }
+    let mut keyed: std::collections::BTreeMap<(usize, usize), Vec<usize>> = std::collections::BTreeMap::new();
+    for (start, end, id) in found {
+        keyed.entry((start, end)).or_default().push(id);
+    }
+    keyed.into_iter().map(|((start, end), ids)| (start, end, ids)).collect()
+}
+
+/// The matches of a byte pattern from a position on, with the bytes
+/// before it as the context of assertions such as word boundaries
+fn find_from<'d>(re: &'d regex::bytes::Regex, data: &'d [u8], at: usize) -> impl Iterator<Item = regex::bytes::Match<'d>> {
+    let first = (at <= data.len()).then(|| re.find_at(data, at)).flatten();
+    std::iter::successors(first, move |m| {
+        // an empty match is passed over
+        let next = m.end() + m.is_empty() as usize;
+        (next <= data.len()).then(|| re.find_at(data, next)).flatten()
+    })
+}
+
+/// Writes raw bytes as text, the valid UTF-8 characters are kept while
+/// control characters, invalid bytes and backslashes are escaped as in
+/// `\x00` and `\\` so that every byte of a match can be told apart.
+fn escape(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => s.push_str("\\\\"),
+                c if c.is_control() => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        s.push_str(&format!("\\x{:02x}", b));
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+        for b in chunk.invalid() {
+            s.push_str(&format!("\\x{:02x}", b));
+        }
+    }
+    s
+}
+
+impl Iterator for Stringer<'_> {
+    type Item = StringerResult;
+
+    /// extracts the next string lazily from the stream
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_next() {
+            Ok(r) => r,
+            Err(e) => {
+                self.end = true;
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// A string borrowed from the slice it was extracted from.
+/// The bytes are in the configured encoding as they are in the input.
+#[derive(Debug, Clone, Copy)]
+pub struct StringerSlice<'a> {
+    /// the bytes of the string in the input
+    bytes: &'a [u8],
+    /// encoding the string was found in
+    encoding: config::Encoding,
+    /// the string is a match of the byte patterns on the raw bytes
+    raw: bool,
+    /// position of the first byte of the string in the input
+    offset: u64,
+}
+
+impl<'a> StringerSlice<'a> {
+    /// the bytes of the string as they are in the input
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// the string itself, only available for ASCII and UTF-8
+    /// since UTF-16 strings need to be converted first
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.encoding {
+            config::Encoding::Ascii | config::Encoding::Utf8 => {
+                std::str::from_utf8(self.bytes).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// the string converted to UTF-8, borrowed when possible
+    pub fn to_str(&self) -> Cow<'a, str> {
+        match self.as_str() {
+            Some(s) => Cow::Borrowed(s),
+            None => Cow::Owned(String::from_utf8_lossy(&self.encoding.to_utf8(self.bytes)).into_owned()),
+        }
+    }
+
+    /// encoding the string was found in
+    pub fn encoding(&self) -> config::Encoding {
+        self.encoding
+    }
+
+    /// checks if the string is a match of the byte patterns on the raw bytes
+    pub fn raw(&self) -> bool {
+        self.raw
+    }
+
+    /// position of the first byte of the string in the input
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// position right after the last byte of the string in the input
+    pub fn end(&self) -> u64 {
+        self.offset + self.bytes.len() as u64
+    }
+}
+
+/// Slices
+/// An iterator over the strings of a slice that borrows every
+/// string from the slice instead of copying it into a result.
+pub struct Slices<'a> {
+    /// the scanner running over the slice
+    stringer: Stringer<'a>,
+    /// the slice the strings are borrowed from
+    data: &'a [u8],
+    /// pieces of a string that are not returned yet in an extraction mode
+    pending: std::collections::VecDeque<StringerSlice<'a>>,
+}
+
+impl<'a> Slices<'a> {
+    /// creates an iterator over the strings in the slice
+    pub fn new(data: &'a [u8], config: config::StringerConfig) -> Self {
+        let mut stringer = Stringer::from_slice(data);
+        stringer.set_config(config);
+        Slices { stringer, data, pending: std::collections::VecDeque::new() }
+    }
+}
+
+impl<'a> Iterator for Slices<'a> {
+    type Item = StringerSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(p) = self.pending.pop_front() {
+            return Some(p);
+        }
+        // the matches of byte patterns are borrowed from the slice as they are
+        if self.stringer.config.raw() {
+            if self.stringer.end {
+                return None;
+            }
+            for (start, end, _) in self.stringer.byte_pieces(self.data, &[], self.data.len()) {
+                if !self.stringer.keeps(&escape(&self.data[start..end])) {
+                    continue;
+                }
+                self.pending.push_back(StringerSlice {
+                    bytes: &self.data[start..end],
+                    encoding: config::Encoding::Utf8,
+                    raw: true,
+                    offset: start as u64,
                 });
             }
+            self.stringer.skip_to_end();
+            return self.pending.pop_front();
+        }
+        // a slice is never read from, so scanning cannot fail
+        while let Some((start, end)) = self.stringer.next_range().ok()? {
+            let slice = StringerSlice {
+                bytes: &self.data[start as usize..end as usize],
+                encoding: self.stringer.config.encoding,
+                raw: false,
+                offset: start,
+            };
+            let Some(ref extract) = self.stringer.config.extract else {
+                if self.stringer.matches(&slice.to_str()) {
+                    return Some(slice);
+                }
+                continue;
+            };
+
+            // the pieces are borrowed from the slice at their own positions
+            let s = slice.to_str();
+            for (a, b, _) in self.stringer.pieces(&s, extract) {
+                if !self.stringer.keeps(&s[a..b]) {
+                    continue;
+                }
+                let from = start as usize + slice.encoding.encoded_len(&s[..a]) as usize;
+                let to = from + slice.encoding.encoded_len(&s[a..b]) as usize;
+                self.pending.push_back(StringerSlice {
+                    bytes: &self.data[from..to],
+                    encoding: slice.encoding,
+                    raw: false,
+                    offset: from as u64,
+                });
+            }
+            if let Some(p) = self.pending.pop_front() {
+                return Some(p);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::Encoding;
+
+    /// ASCII, UTF-8 and UTF-16 strings between bytes that are never printable
+    fn sample() -> Vec<u8> {
+        let mut data = Vec::new();
+        for i in 0..40 {
+            data.extend_from_slice(format!("ascii string number {}", i).as_bytes());
+            data.extend_from_slice(&[0, 1, 0xff, 0x80]);
+            data.extend_from_slice("ünïcödé strîng №".as_bytes());
+            data.push(0);
+            for u in "wide string".encode_utf16() {
+                data.extend_from_slice(&u.to_le_bytes());
+            }
+            data.extend_from_slice(&[0, 0, 0, 0]);
+            for u in "big endian".encode_utf16() {
+                data.extend_from_slice(&u.to_be_bytes());
+            }
+            data.extend_from_slice(&[0, 0, 7, 7]);
+        }
+        data
+    }
+
+    /// the strings of a stream with their offsets
+    fn scan(data: &[u8], config: &config::StringerConfig) -> Vec<(u64, u64, String)> {
+        let mut s = Stringer::from_reader(data);
+        s.set_config(config.clone());
+        s.map(|r| (r.offset, r.end, r.string.to_str().unwrap().to_string())).collect()
+    }
+
+    /// the text of a few strings in an encoding between NUL code units
+    fn encoded(texts: &[&str], encoding: Encoding) -> Vec<u8> {
+        let mut data = Vec::new();
+        for t in texts {
+            data.extend_from_slice(&[0; 4]);
+            match encoding {
+                Encoding::Utf16Le => data.extend(t.encode_utf16().flat_map(|u| u.to_le_bytes())),
+                Encoding::Utf16Be => data.extend(t.encode_utf16().flat_map(|u| u.to_be_bytes())),
+                _ => data.extend_from_slice(t.as_bytes()),
+            }
+        }
+        data.extend_from_slice(&[0; 4]);
+        data
+    }
+
+    #[test]
+    fn pattern_ids_and_excludes() {
+        let data = encoded(&["GLIBC_2.34", "malloc", "GLIBC_PRIVATE", "free", "GCC: (GNU) 14.2"], Encoding::Ascii);
+        let mut config = config::StringerConfig::default();
+        config.set_patterns(vec!["GLIBC".to_string(), "^[a-z]+$".to_string(), "GCC|malloc".to_string()]).unwrap();
+        let found = |config: &config::StringerConfig| -> Vec<(String, Vec<usize>)> {
+            let mut s = Stringer::from_slice(&data);
+            s.set_config(config.clone());
+            s.map(|r| (r.as_str().to_string(), r.patterns().to_vec())).collect()
         };
+        let owned = |found: &[(&str, Vec<usize>)]| -> Vec<(String, Vec<usize>)> {
+            found.iter().map(|(s, ids)| (s.to_string(), ids.clone())).collect()
+        };
+        assert_eq!(found(&config), owned(&[
+            ("GLIBC_2.34", vec![0]),
+            ("malloc", vec![1, 2]),
+            ("GLIBC_PRIVATE", vec![0]),
+            ("free", vec![1]),
+            ("GCC: (GNU) 14.2", vec![2]),
+        ]));
+
+        // the exclude patterns drop strings that the patterns keep
+        config.set_exclude_patterns(vec!["PRIVATE".to_string(), "^(malloc|free)$".to_string()]).unwrap();
+        assert_eq!(found(&config), owned(&[("GLIBC_2.34", vec![0]), ("GCC: (GNU) 14.2", vec![2])]));
+        // and every other string without patterns
+        config.set_patterns(Vec::new()).unwrap();
+        assert_eq!(found(&config), owned(&[("GLIBC_2.34", vec![]), ("GCC: (GNU) 14.2", vec![])]));
     }
 
-    /// reads all the available strings in the stream
-    /// and stores them in results
-    pub fn read_strings(self: &mut Self) {
-        while !self.end {
-            let buff = self.read();
-            match buff {
-                Some(buff) => {
-                    let res = self.to_stringer(buff);
-                    match res {
-                        Some(r) => {
-                            self.results.push(r);
-                        },
-                        None => {continue;},
-                    };
-                },
-                None => { continue; }
-            };
+    #[test]
+    fn extracted_offsets() {
+        let texts = ["Grüße aus Köln user=jürgen id=42", "ünïcödé key=välue and more=ßtuff"];
+        for encoding in [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            let data = encoded(&texts, encoding);
+            let mut config = config::StringerConfig::default();
+            config.set_encoding(encoding);
+            config.set_utf16_unicode(true);
+            config.set_patterns(vec![r"\w+=\w+".to_string()]).unwrap();
+            config.set_extract(Some(config::Extract::Matches));
+            let found = scan(&data, &config);
+            let strings: Vec<&str> = found.iter().map(|r| r.2.as_str()).collect();
+            assert_eq!(strings, ["user=jürgen", "id=42", "key=välue", "more=ßtuff"], "{:?}", encoding);
+
+            // the offsets are those of the bytes of each piece in the input
+            for (offset, end, s) in &found {
+                let bytes = &data[*offset as usize..*end as usize];
+                assert_eq!(String::from_utf8(encoding.to_utf8(bytes)).unwrap(), *s, "{:?}", encoding);
+            }
+        }
+    }
+
+    #[test]
+    fn extracted_groups() {
+        let data = encoded(&["login user=alice@example from home"], Encoding::Ascii);
+        let mut config = config::StringerConfig::default();
+        config.set_patterns(vec![r"(?P<user>\w+)@(?P<host>\w+)".to_string(), r"user=(?P<user>\w+)".to_string()])
+            .unwrap();
+        config.set_extract(Some(config::Extract::Groups(vec!["user".to_string(), "host".to_string()])));
+        let mut s = Stringer::from_slice(&data);
+        s.set_config(config);
+        // a group found by both patterns is a single piece with both ids
+        let found: Vec<(u64, String, Vec<usize>)> =
+            s.map(|r| (r.offset(), r.as_str().to_string(), r.patterns().to_vec())).collect();
+        assert_eq!(found, vec![
+            (15, "alice".to_string(), vec![0, 1]),
+            (21, "example".to_string(), vec![0]),
+        ]);
+    }
+
+    #[test]
+    fn slices_and_stringer() {
+        let texts = ["Grüße aus Köln user=jürgen id=42", "plain ascii words", "ünïcödé key=välue"];
+        let extracts = [None, Some(config::Extract::Matches), Some(config::Extract::Groups(vec!["v".to_string()]))];
+        for encoding in [Encoding::Ascii, Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            for extract in &extracts {
+                let data = encoded(&texts, encoding);
+                let mut config = config::StringerConfig::default();
+                config.set_encoding(encoding);
+                config.set_utf16_unicode(true);
+                if extract.is_some() {
+                    config.set_patterns(vec![r"\w+=(?P<v>\w+)".to_string()]).unwrap();
+                }
+                config.set_extract(extract.clone());
+
+                let mut s = Stringer::from_slice(&data);
+                s.set_config(config.clone());
+                let results: Vec<(u64, u64, String)> =
+                    s.map(|r| (r.offset(), r.end(), r.as_str().to_string())).collect();
+                let slices: Vec<(u64, u64, String)> = Slices::new(&data, config)
+                    .map(|s| (s.offset(), s.end(), s.to_str().into_owned()))
+                    .collect();
+                assert!(!results.is_empty());
+                assert_eq!(slices, results, "{:?} {:?}", encoding, extract);
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        let data = sample();
+        for encoding in [Encoding::Ascii, Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            for (whitespace, split, max) in [(false, 0, 0), (true, 0, 0), (true, 5, 0), (true, 0, 16)] {
+                let mut config = config::StringerConfig::default();
+                config.set_encoding(encoding);
+                config.whitespace_include(whitespace);
+                config.split(split);
+                config.set_window_max_size(max);
+                config.set_chunk_size(1 << 20);
+                let whole = scan(&data, &config);
+                assert!(max != 0 || !whole.is_empty());
+
+                for size in [1, 2, 3, 7, 64] {
+                    config.set_chunk_size(size);
+                    assert_eq!(scan(&data, &config), whole, "{:?} in chunks of {}", encoding, size);
+                }
+
+                // the byte table finds the strings of the per character checks
+                config.set_table_scan(false);
+                assert_eq!(scan(&data, &config), whole, "{:?} without the table", encoding);
+            }
+        }
+    }
 
+    #[test]
+    fn byte_patterns_in_chunks() {
+        let data = sample();
+        let patterns = [r"\bstring\b", r"number \d+", r"(?-u:[\x80-\xff]+)", r"\w+ing"];
+        for n in [1, patterns.len()] {
+            let mut config = config::StringerConfig::default();
+            // the matches are at most 16 bytes long
+            config.set_match_overlap(16);
+            config.set_byte_patterns(patterns[..n].iter().map(|p| p.to_string()).collect()).unwrap();
+            let whole: Vec<StringerResult> = {
+                let mut s = Stringer::from_slice(&data);
+                s.set_config(config.clone());
+                s.collect()
+            };
+            let found = |r: &[StringerResult]| -> Vec<(u64, u64, String, Vec<usize>)> {
+                r.iter().map(|r| (r.offset(), r.end(), r.as_str().to_string(), r.patterns().to_vec())).collect()
+            };
+            assert!(whole.len() >= 40);
+            assert!(whole.iter().all(|r| r.raw() && r.encoding_name() == "raw"));
+            for chunk in [1, 2, 3, 7, 64, 1 << 20] {
+                config.set_chunk_size(chunk);
+                let mut s = Stringer::from_reader(&data[..]);
+                s.set_config(config.clone());
+                let chunked: Vec<StringerResult> = s.collect();
+                assert_eq!(found(&chunked), found(&whole), "{} patterns in chunks of {}", n, chunk);
+            }
         }
+    }
+
+    #[test]
+    fn byte_pattern_length() {
+        let mut data = b"the password comes next: here".to_vec();
+        data.extend_from_slice(b"\0pass\xff\x01");
+        let mut config = config::StringerConfig::default();
+        config.length_include(true);
+        config.set_byte_patterns(vec![r"here\x00pass".to_string(), r"(?-u:\xff\x01)".to_string()]).unwrap();
+        let mut s = Stringer::from_slice(&data);
+        s.set_config(config);
+        let found: Vec<(u64, u64, String, Option<u64>)> =
+            s.map(|r| (r.offset(), r.end(), r.as_str().to_string(), r.length())).collect();
+        assert_eq!(found, vec![
+            (25, 34, r"here\x00pass".to_string(), Some(9)),
+            (34, 36, r"\xff\x01".to_string(), Some(2)),
+        ]);
+    }
 
-        match self.config.regex{
-            Some(ref r) => {
-                // this clone is gonna cost a lot.
-                let res: Vec<StringerResult> = 
-                    self.results
-                    .iter()
-                    .filter({ |x|  
-                        r.is_match(x.string.to_str().unwrap())
-                    }).cloned()
-                .collect();
-                self.results = res;
+    #[test]
+    fn utf16_of_ascii_data() {
+        // symbol names and text that must not be read as UTF-16 at either alignment
+        let mut data = b"_ITM_deregisterTMCloneTable\0__libc_start_main\0GLIBC_2.34\0".repeat(8);
+        data.extend_from_slice(b"xThe quick brown fox jumps over the lazy dog, 0123456789!");
+        for encoding in [Encoding::Utf16Le, Encoding::Utf16Be] {
+            for unicode in [false, true] {
+                let mut config = config::StringerConfig::default();
+                config.set_encoding(encoding);
+                config.set_utf16_unicode(unicode);
+                assert_eq!(scan(&data, &config), Vec::new(), "{:?}", encoding);
             }
-            _ => {}
+        }
+    }
+
+    #[test]
+    fn utf16_text() {
+        let mut data = vec![0xff, 0xfe];
+        for u in "Hello Grüße Привет".encode_utf16() {
+            data.extend_from_slice(&u.to_le_bytes());
+        }
+        let mut config = config::StringerConfig::default();
+        config.set_encoding(Encoding::Utf16Le);
+        config.whitespace_include(false);
+        let strings = |c: &config::StringerConfig| -> Vec<String> {
+            scan(&data, c).into_iter().map(|r| r.2).collect()
         };
+        assert_eq!(strings(&config), ["Hello"]);
+
+        config.set_categories(config::UnicodeCategories::from_names(&["letters"]).unwrap());
+        config.set_utf16_unicode(true);
+        assert_eq!(strings(&config), ["Hello", "Grüße", "Привет"]);
+    }
+
+    #[test]
+    fn utf16_localized() {
+        let text = [
+            "C’est l’été déjà",
+            "Привет—мир",
+            "Đường phố Hà Nội",
+            "Ελληνικά ἀρχή",
+            "Ā Ѐ ĀЀ ā",
+        ];
+        let cjk = ["一二三四五 中文字符", "가나다라 한국어", "日本語　のテキスト"];
+        for encoding in [Encoding::Utf16Le, Encoding::Utf16Be] {
+            let mut data = Vec::new();
+            for s in text.iter().chain(&cjk) {
+                for u in s.encode_utf16().chain([0]) {
+                    data.extend_from_slice(&match encoding {
+                        Encoding::Utf16Le => u.to_le_bytes(),
+                        _ => u.to_be_bytes(),
+                    });
+                }
+            }
+            let mut config = config::StringerConfig::default();
+            config.set_encoding(encoding);
+            config.set_utf16_unicode(true);
+            let strings = |c: &config::StringerConfig| -> Vec<String> {
+                scan(&data, c).into_iter().map(|r| r.2).collect()
+            };
+            let found = strings(&config);
+            assert!(text.iter().all(|s| found.contains(&s.to_string())), "{:?} {:?}", encoding, found);
+
+            config.set_utf16_cjk(true);
+            for chunk in [1, 3, 7, 1 << 20] {
+                config.set_chunk_size(chunk);
+                assert_eq!(strings(&config), text.iter().chain(&cjk).copied().collect::<Vec<_>>(), "{:?}", encoding);
+            }
+        }
+    }
+
+    #[test]
+    fn magic_of_text() {
+        let text = b"BZh9 and \x1f\x8b\x08 start some text files".to_vec();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut gzip, &text).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let fake = [&[0x1f, 0x8b, 0x08][..], &text].concat();
+
+        let mut config = config::StringerConfig::default();
+        config.decompress(true);
+        for (data, compressed) in [(&text, false), (&fake, false), (&gzip, true)] {
+            for slice in [false, true] {
+                let mut s = match slice {
+                    true => Stringer::from_slice(data),
+                    false => Stringer::from_reader(&data[..]),
+                };
+                s.set_config(config.clone());
+                let results: Vec<StringerResult> = s.decompressed().unwrap().collect();
+                let strings: Vec<&str> = results.iter().map(|r| r.as_str()).collect();
+                assert_eq!(strings.last(), Some(&" start some text files"), "{:?}", strings);
+                assert!(results.iter().all(|r| r.compression().is_empty() != compressed));
+            }
+        }
     }
 }
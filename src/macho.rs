@@ -0,0 +1,341 @@
+use goblin::mach::constants::cputype::{get_arch_from_flag, get_arch_name_from_types, CPU_SUBTYPE_MASK};
+use goblin::mach::constants::{S_ATTR_PURE_INSTRUCTIONS, S_ATTR_SOME_INSTRUCTIONS, SECTION_TYPE, S_ZEROFILL};
+use goblin::mach::fat::{FatHeader, FAT_MAGIC};
+use goblin::mach::header::{MH_CIGAM, MH_CIGAM_64, MH_MAGIC, MH_MAGIC_64};
+use goblin::mach::{MachO, MultiArch};
+
+use crate::section::Section;
+
+/// Name of the architecture selection that keeps every slice
+pub const ALL: &str = "all";
+/// Most slices a universal binary is expected to have, Java class
+/// files start with the same magic number followed by a larger version
+const MAX_SLICES: u32 = 20;
+
+/// A binary of a single architecture in a file
+struct Slice {
+    /// name of the architecture, such as `arm64`
+    name: String,
+    /// position of the first byte of the slice in the file
+    offset: usize,
+    /// number of bytes of the slice
+    size: usize,
+}
+
+/// name of an architecture from the cpu types of a header
+fn arch_name(cputype: u32, cpusubtype: u32) -> String {
+    match get_arch_name_from_types(cputype, cpusubtype & !CPU_SUBTYPE_MASK) {
+        Some(n) => n.to_string(),
+        None => format!("cpu{}", cputype),
+    }
+}
+
+/// checks if an architecture name is known, `all` selects every slice
+pub fn is_arch(name: &str) -> bool {
+    name == ALL || get_arch_from_flag(name).is_some()
+}
+
+/// checks if the data is a Mach-O file or a universal binary
+pub fn is_macho(data: &[u8]) -> bool {
+    let Some(magic) = data.get(..4) else {
+        return false;
+    };
+    let be = u32::from_be_bytes([magic[0], magic[1], magic[2], magic[3]]);
+    match be {
+        MH_MAGIC | MH_CIGAM | MH_MAGIC_64 | MH_CIGAM_64 => true,
+        FAT_MAGIC => FatHeader::parse(data).is_ok_and(|h| h.nfat_arch > 0 && h.nfat_arch <= MAX_SLICES),
+        _ => false,
+    }
+}
+
+/// the architecture slices of a Mach-O file, a thin file is a single slice
+fn slices(data: &[u8]) -> Result<Vec<Slice>, goblin::error::Error> {
+    let header = FatHeader::parse(data)?;
+    if header.magic != FAT_MAGIC {
+        let macho = MachO::parse(data, 0)?;
+        return Ok(vec![Slice {
+            name: arch_name(macho.header.cputype, macho.header.cpusubtype),
+            offset: 0,
+            size: data.len(),
+        }]);
+    }
+
+    let mut slices = Vec::new();
+    for arch in MultiArch::new(data)?.arches()? {
+        let (offset, size) = (arch.offset as usize, arch.size as usize);
+        // slices that reach past the end of a truncated file
+        if offset.checked_add(size).is_none_or(|end| end > data.len()) {
+            continue;
+        }
+        slices.push(Slice {
+            name: arch_name(arch.cputype, arch.cpusubtype),
+            offset,
+            size,
+        });
+    }
+    Ok(slices)
+}
+
+/// names of the architectures of the slices of a Mach-O file,
+/// none when the file cannot be parsed
+pub fn arches(data: &[u8]) -> Vec<String> {
+    slices(data).map(|s| s.into_iter().map(|s| s.name).collect()).unwrap_or_default()
+}
+
+/// Returns the slices of a Mach-O file or a universal binary that
+/// match the architecture, all of them without one. With `whole`
+/// each slice is returned as a single section without a name,
+/// otherwise the sections of the slices are returned, named after
+/// their segment and section such as `__TEXT,__cstring`.
+/// Sections that are neither code nor zero filled are the data sections.
+pub fn sections(data: &[u8], arch: Option<&str>, whole: bool) -> Result<Vec<Section>, goblin::error::Error> {
+    let mut sections = Vec::new();
+    for slice in slices(data)? {
+        if arch.is_some_and(|a| a != ALL && a != slice.name) {
+            continue;
+        }
+        if whole {
+            sections.push(Section {
+                name: String::new(),
+                offset: slice.offset as u64,
+                size: slice.size as u64,
+                address: None,
+                data: true,
+                encoding: None,
+                slice: Some(slice.name),
+            });
+            continue;
+        }
+
+        // a slice that is a static library has no segments
+        let bytes = &data[slice.offset..slice.offset + slice.size];
+        let Ok(macho) = MachO::parse(bytes, 0) else {
+            continue;
+        };
+        for segment in macho.segments.iter() {
+            // a segment whose sections are not parsed is skipped on its own
+            let Ok(found) = segment.sections() else {
+                continue;
+            };
+            for (s, contents) in found {
+                // zero filled sections have no contents in the file
+                if s.flags & SECTION_TYPE == S_ZEROFILL || contents.is_empty() {
+                    continue;
+                }
+                sections.push(Section {
+                    name: format!("{},{}", s.segname().unwrap_or_default(), s.name().unwrap_or_default()),
+                    offset: (slice.offset + s.offset as usize) as u64,
+                    size: contents.len() as u64,
+                    address: Some(s.addr),
+                    data: s.flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS) == 0,
+                    encoding: None,
+                    slice: Some(slice.name.clone()),
+                });
+            }
+        }
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StringerConfig;
+    use crate::section::SectionFilter;
+    use crate::Stringer;
+
+    /// cpu types of x86_64 and arm64
+    const X86_64: (u32, u32) = (0x0100_0007, 3);
+    const ARM64: (u32, u32) = (0x0100_000c, 0);
+    /// flags of a section of instructions
+    const CODE: u32 = S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS;
+
+    /// a segment of a thin file, its sections are given as their name,
+    /// flags and contents, `nsects` overrides the number of its sections
+    struct Segment<'s> {
+        name: &'s str,
+        nsects: Option<u32>,
+        sections: Vec<(&'s str, u32, &'s [u8])>,
+    }
+
+    fn name16(name: &str) -> [u8; 16] {
+        let mut n = [0; 16];
+        n[..name.len()].copy_from_slice(name.as_bytes());
+        n
+    }
+
+    /// a little endian 64-bit Mach-O file with the segments
+    fn macho_of(cpu: (u32, u32), segments: &[Segment]) -> Vec<u8> {
+        let commands: usize = segments.iter().map(|s| 72 + 80 * s.sections.len()).sum();
+        let mut data = Vec::new();
+        for v in [MH_MAGIC_64, cpu.0, cpu.1, 2, segments.len() as u32, commands as u32, 0, 0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+
+        let mut at = 32 + commands;
+        let mut contents = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            let size: usize = segment.sections.iter().map(|s| s.2.len()).sum();
+            let vmaddr = 0x1000 * (i as u64 + 1);
+            data.extend_from_slice(&0x19u32.to_le_bytes());
+            data.extend_from_slice(&(72 + 80 * segment.sections.len() as u32).to_le_bytes());
+            data.extend_from_slice(&name16(segment.name));
+            for v in [vmaddr, size as u64, at as u64, size as u64] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+            let nsects = segment.nsects.unwrap_or(segment.sections.len() as u32);
+            for v in [7, 5, nsects, 0] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+
+            let mut addr = vmaddr;
+            for (name, flags, bytes) in &segment.sections {
+                data.extend_from_slice(&name16(name));
+                data.extend_from_slice(&name16(segment.name));
+                data.extend_from_slice(&addr.to_le_bytes());
+                data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+                for v in [at as u32, 0, 0, 0, *flags, 0, 0, 0] {
+                    data.extend_from_slice(&v.to_le_bytes());
+                }
+                addr += bytes.len() as u64;
+                at += bytes.len();
+                contents.extend_from_slice(bytes);
+            }
+        }
+        data.extend_from_slice(&contents);
+        data
+    }
+
+    /// a universal binary of the thin files, each aligned to 4 KiB
+    fn fat_of(slices: &[((u32, u32), Vec<u8>)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for v in [FAT_MAGIC, slices.len() as u32] {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        let mut at = 0x1000;
+        for (cpu, bytes) in slices {
+            for v in [cpu.0, cpu.1, at as u32, bytes.len() as u32, 12] {
+                data.extend_from_slice(&v.to_be_bytes());
+            }
+            at += bytes.len().div_ceil(0x1000) * 0x1000;
+        }
+        for (_, bytes) in slices {
+            data.resize(data.len().div_ceil(0x1000) * 0x1000, 0);
+            data.extend_from_slice(bytes);
+        }
+        data
+    }
+
+    fn thin(cpu: (u32, u32), text: &'static [u8]) -> Vec<u8> {
+        macho_of(cpu, &[
+            Segment {
+                name: "__TEXT",
+                nsects: None,
+                sections: vec![("__text", CODE, b"\xffcode string\xc3"), ("__cstring", 2, text)],
+            },
+            Segment { name: "__DATA", nsects: None, sections: vec![("__data", 0, b"\0data string\0")] },
+        ])
+    }
+
+    /// a string with its slice and section
+    type Found = (String, Option<String>, Option<String>);
+
+    /// the strings of the input under the config and the
+    /// slices there are when none has the architecture
+    fn strings(data: &[u8], filter: SectionFilter, arch: Option<&str>) -> (Vec<Found>, Option<Vec<String>>) {
+        let mut config = StringerConfig::default();
+        config.set_sections(filter);
+        config.set_arch(arch.map(String::from));
+        let mut s = Stringer::from_slice(data);
+        s.set_config(config);
+        s.read_strings_sections().unwrap();
+        let found = s.results
+            .iter()
+            .map(|r| (r.as_str().to_string(), r.slice().map(String::from), r.section().map(String::from)))
+            .collect();
+        (found, s.unmatched_arch().map(|a| a.to_vec()))
+    }
+
+    fn found(s: &str, slice: &str, section: Option<&str>) -> Found {
+        (s.to_string(), Some(slice.to_string()), section.map(String::from))
+    }
+
+    #[test]
+    fn thin_sections() {
+        let data = thin(X86_64, b"c string\0");
+        assert!(is_macho(&data));
+        let listed: Vec<(String, bool, Option<u64>, Option<String>)> = sections(&data, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.name, s.data, s.address, s.slice))
+            .collect();
+        let x86 = Some("x86_64".to_string());
+        assert_eq!(listed, vec![
+            ("__TEXT,__text".to_string(), false, Some(0x1000), x86.clone()),
+            ("__TEXT,__cstring".to_string(), true, Some(0x100d), x86.clone()),
+            ("__DATA,__data".to_string(), true, Some(0x2000), x86.clone()),
+        ]);
+
+        // sections are selected by their full name, their segment or their own name
+        let names = |n: &[&str]| SectionFilter::Names(n.iter().map(|n| n.to_string()).collect());
+        assert_eq!(strings(&data, names(&["__TEXT,__cstring"]), None).0, vec![
+            found("c string", "x86_64", Some("__TEXT,__cstring")),
+        ]);
+        assert_eq!(strings(&data, names(&["__data"]), None).0, vec![
+            found("data string", "x86_64", Some("__DATA,__data")),
+        ]);
+        assert_eq!(strings(&data, names(&["__TEXT"]), None).0, vec![
+            found("code string", "x86_64", Some("__TEXT,__text")),
+            found("c string", "x86_64", Some("__TEXT,__cstring")),
+        ]);
+    }
+
+    #[test]
+    fn fat_slices() {
+        let data = fat_of(&[(X86_64, thin(X86_64, b"intel string\0")), (ARM64, thin(ARM64, b"arm string\0"))]);
+        assert!(is_macho(&data));
+        assert_eq!(arches(&data), vec!["x86_64".to_string(), "arm64".to_string()]);
+
+        // a whole slice is a section without a name
+        let whole: Vec<(u64, Option<String>)> = sections(&data, Some("arm64"), true)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.offset, s.slice))
+            .collect();
+        assert_eq!(whole, vec![(0x2000, Some("arm64".to_string()))]);
+        assert_eq!(sections(&data, Some(ALL), true).unwrap().len(), 2);
+        assert_eq!(sections(&data, None, false).unwrap().len(), 6);
+
+        let (arm, unmatched) = strings(&data, SectionFilter::All, Some("arm64"));
+        assert!(arm.contains(&found("arm string", "arm64", None)));
+        assert!(arm.iter().all(|r| r.1.as_deref() == Some("arm64") && r.0 != "intel string"));
+        assert_eq!(unmatched, None);
+
+        let (data_sections, _) = strings(&data, SectionFilter::Data, Some(ALL));
+        assert_eq!(data_sections, vec![
+            found("intel string", "x86_64", Some("__TEXT,__cstring")),
+            found("data string", "x86_64", Some("__DATA,__data")),
+            found("arm string", "arm64", Some("__TEXT,__cstring")),
+            found("data string", "arm64", Some("__DATA,__data")),
+        ]);
+
+        // an architecture without a slice finds nothing and tells the slices there are
+        let (none, unmatched) = strings(&data, SectionFilter::All, Some("i386"));
+        assert_eq!(none, Vec::new());
+        assert_eq!(unmatched, Some(vec!["x86_64".to_string(), "arm64".to_string()]));
+    }
+
+    #[test]
+    fn bad_segment() {
+        // the sections of the first segment run past the end of the file
+        let data = macho_of(X86_64, &[
+            Segment { name: "__TEXT", nsects: Some(1 << 20), sections: vec![("__cstring", 2, b"text string\0")] },
+            Segment { name: "__DATA", nsects: None, sections: vec![("__data", 0, b"\0data string\0")] },
+        ]);
+        let names: Vec<String> = sections(&data, None, false).unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["__DATA,__data".to_string()]);
+        assert_eq!(strings(&data, SectionFilter::Data, None).0, vec![
+            found("data string", "x86_64", Some("__DATA,__data")),
+        ]);
+    }
+}
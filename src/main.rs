@@ -1,80 +1,159 @@
 extern crate stringer;
 
-fn stringer_write(path: String, conf: stringer::config::StringerConfig, out: Option<String>) {
-    let file = std::fs::File::open(path);
-    match file {
-        Ok(_) => {}
-        Err(_) => {
-            println!("cannot open file");
-            return;
-        },
+use stringer::config::StringerConfig;
+use stringer::error::StringerError;
+use stringer::writer::Writer;
+
+/// extracts the strings of a single input into the writer.
+/// Reading errors are returned with the path of the input,
+/// writing errors are returned as output errors.
+/// Members of archives that cannot be read are reported as they happen.
+fn stringer_file<W: std::io::Write>(
+    input: &stringer::input::Input,
+    source: Option<String>,
+    conf: &StringerConfig,
+    w: &mut Writer<W>,
+    failed: &mut Option<StringerError>,
+) -> Result<(), StringerError> {
+    // files are mapped when possible instead of being read
+    let mut r = match input {
+        stringer::input::Input::File(p) => stringer::Stringer::from_path(p)?,
+        stringer::input::Input::Stdin => stringer::Stringer::from_reader(input.open()?),
     };
+    r.set_config(conf.clone());
+    // members of archives are told apart by the name of the input
+    let name = source.clone().unwrap_or_else(|| input.name());
+    if let Some(s) = source {
+        r.set_source(s);
+    }
 
-    let mut file = file.unwrap();
-    let result = stringer::Stringer::new(&mut file);
-    match result {
-        Ok(mut r) => {
-            r.set_config(conf);
-            r.read_strings();
-            match out {
-                Some(p) => {
-                    let f = std::fs::File::create_new(p);
-                    match f {
-                        Ok(mut f) => {
-                            match stringer::writer::write(&mut f, &r.results) {
-                                Ok(_) => {}
-                                Err(_) => {
-                                    println!("unable to write data to file");
-                                }
-                            };
-                        }
-                        Err(_) => {}
-                    };
-                },
-                None => {
-                    let mut stdout = std::io::stdout();
-                    let _lock = stdout.lock();
-                    match stringer::writer::write(&mut stdout, &r.results) {
-                        Ok(_) => {}
-                        Err(_) => {
-                            println!("unable to write data");
-                        }
-                    };
-                    // drop(lock);
-                }
-            }
+    let res = stringer_scan(r, &name, conf, w, failed);
+    match input {
+        stringer::input::Input::File(p) => res.map_err(|e| e.with_path(p)),
+        stringer::input::Input::Stdin => res,
+    }
+}
+
+/// Extracts the strings of an opened input into the writer. The members
+/// of archives are extracted one by one as inputs named after the archive
+/// and their path, a member that cannot be read is reported and skipped.
+fn stringer_scan<W: std::io::Write>(
+    r: stringer::Stringer,
+    name: &str,
+    conf: &StringerConfig,
+    w: &mut Writer<W>,
+    failed: &mut Option<StringerError>,
+) -> Result<(), StringerError> {
+    // compressed inputs are streamed through their decoders
+    let mut r = r.decompressed()?;
+
+    if let Some(kind) = r.archive()? {
+        r.set_source(name.to_string());
+        return r.read_members(kind, |source, m| {
+            let res = stringer_scan(m, source, conf, w, failed);
+            report(res.map_err(|e| e.with_path(source)), failed)
+        });
+    }
+
+    // sections of object files are only known once the headers are parsed
+    if conf.threads != 1 || r.sectioned()? {
+        r.read_strings_sections()?;
+        if let (Some(arches), Some(arch)) = (r.unmatched_arch(), &conf.arch) {
+            eprintln!("stringer: {}: no slice of architecture {}, only {}", name, arch, arches.join(", "));
         }
-        Err(_) => {}
+        for res in &r.results {
+            w.write(res)?;
+        }
+        return Ok(());
     }
 
+    // results are written as soon as they are extracted
+    while let Some(res) = r.read_next()? {
+        w.write(&res)?;
+    }
+    Ok(())
 }
 
-fn main() {
-    let args = stringer::args::Args::parse_args();
-    match args {
-        Ok(arg) => {
-            let output = &arg.output;
-            let output = match output {
-                Some(s)  => { Some(s.clone()) },
-                None =>  { None },
-            };
-            let input = &arg.input;
-            let input = match input {
-                Some(i) => { Some(i.clone()) },
-                None =>  { None }
-            };
+/// Reports an input that cannot be read and keeps its error as the
+/// last one. Output errors are returned since nothing more can be written.
+fn report(res: Result<(), StringerError>, failed: &mut Option<StringerError>) -> Result<(), StringerError> {
+    match res {
+        Ok(_) => Ok(()),
+        Err(e @ StringerError::Output(_)) => Err(e),
+        Err(e) => {
+            eprintln!("stringer: {}", e);
+            *failed = Some(e);
+            Ok(())
+        }
+    }
+}
 
-            let conf = stringer::config::StringerConfig::from(arg);
-            match input {
-                Some(i) => {
-                        stringer_write(i,  conf, output);
-                },
-                None  => {}
-            };
-        },
-        Err(_) => {
-            panic!("error parsing arguments");
+/// extracts the strings of all the inputs into the output.
+/// An input that cannot be read is reported and skipped, the
+/// last of those errors is returned once every input is read.
+fn stringer_write(
+    paths: Vec<String>,
+    conf: StringerConfig,
+    walk: stringer::input::WalkOptions,
+    out: Option<String>,
+) -> Result<(), StringerError> {
+    let w: Box<dyn std::io::Write> = match out {
+        Some(p) => {
+            let f = std::fs::File::create_new(p).map_err(StringerError::output)?;
+            Box::new(std::io::BufWriter::new(f))
         }
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
     };
+    let mut w = Writer::new(w, conf.output_format);
 
+    let inputs = stringer::input::expand(&paths, &walk)?;
+
+    // results are tagged with the input they come from
+    // once more than a single input is read
+    let tag = stringer::input::is_many(&paths);
+    let mut failed = None;
+    for input in inputs {
+        let res = input.and_then(|i| {
+            let source = if tag { Some(i.name()) } else { None };
+            stringer_file(&i, source, &conf, &mut w, &mut failed)
+        });
+        report(res, &mut failed)?;
+    }
+
+    w.finish()?;
+    match failed {
+        Some(e) => Err(e),
+        None if conf.fail_on_findings && w.findings() > 0 => Err(StringerError::Findings(w.findings())),
+        None => Ok(()),
+    }
+}
+
+fn run() -> Result<(), StringerError> {
+    let arg = stringer::args::Args::parse_args()?;
+    let output = arg.output.clone();
+    let input = arg.input.clone().unwrap_or_default();
+    let walk = stringer::input::WalkOptions::from(&arg);
+
+    // pattern files are not inputs, their errors are not reported yet
+    let conf = StringerConfig::try_from(arg).inspect_err(|e| {
+        if let StringerError::Io { .. } = e {
+            eprintln!("stringer: {}", e);
+        }
+    })?;
+    stringer_write(input,  conf, walk, output)
+}
+
+fn main() {
+    match run() {
+        Ok(_) => {}
+        // input errors are reported as they happen
+        Err(e @ StringerError::Io { .. }) => std::process::exit(e.exit_code()),
+        Err(e) => {
+            // a closed pipe is not worth a message
+            if !e.broken_pipe() {
+                eprintln!("stringer: {}", e);
+            }
+            std::process::exit(e.exit_code());
+        }
+    };
 }
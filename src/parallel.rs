@@ -0,0 +1,144 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::config::{Encoding, StringerConfig};
+use crate::{Stringer, StringerResult};
+
+/// Returns the first position at or after `from` where a sequential
+/// scan is known to have no string in progress, so a new scan that
+/// starts there finds exactly the strings the sequential scan finds.
+///
+/// For ASCII and UTF-8 that is right after a non printable byte below
+/// 0x80, since such a byte is never part of a multibyte sequence.
+/// For UTF-16 it is after four zero bytes: a scan at any alignment
+/// runs into a NUL code unit within them and cannot skip past them.
+fn sync_point(probe: &Stringer, data: &[u8], from: usize) -> Option<usize> {
+    match probe.config.encoding {
+        Encoding::Ascii | Encoding::Utf8 => data[from..]
+            .iter()
+            .position(|&b| b < 0x80 && !probe.printable(b as char))
+            .map(|i| from + i + 1),
+        Encoding::Utf16Le | Encoding::Utf16Be => data[from..]
+            .windows(4)
+            .position(|w| w == [0, 0, 0, 0])
+            .map(|i| from + i + 3),
+    }
+}
+
+/// splits the data into pieces that can be scanned on their own
+fn pieces(probe: &Stringer, data: &[u8], threads: usize) -> Vec<(usize, usize)> {
+    // pieces are at least the piece size of the config
+    let size = (data.len() / (threads * 4)).max(probe.config.piece_size.max(1) as usize);
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        // a piece reaches until the next point where the scan is in
+        // sync, which overlaps the piece into the next one
+        let end = match start.checked_add(size) {
+            Some(b) if b < data.len() => sync_point(probe, data, b).unwrap_or(data.len()),
+            _ => data.len(),
+        };
+        pieces.push((start, end));
+        start = end;
+    }
+    pieces
+}
+
+/// Scans the data on the given number of threads and returns the
+/// results in offset order, identical to a sequential scan.
+/// The data is split into pieces that are scanned by a pool of
+/// threads, each piece extended until the strings that straddle
+/// its end are complete.
+pub fn scan(
+    data: &[u8],
+    config: &StringerConfig,
+    source: Option<String>,
+    threads: usize,
+) -> Vec<StringerResult> {
+    let mut probe = Stringer::from_slice(&[]);
+    probe.set_config(config.clone());
+    let threads = threads.max(1);
+    let pieces = pieces(&probe, data, threads);
+
+    let next = AtomicUsize::new(0);
+    let mut done: Vec<(usize, Vec<StringerResult>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(pieces.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut out = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&(start, end)) = pieces.get(i) else {
+                            break;
+                        };
+
+                        let mut s = Stringer::from_slice(&data[start..end]);
+                        s.set_config(config.clone());
+                        s.source = source.clone();
+                        // offsets are positions in the whole data
+                        s.base = start as u64;
+                        s.pos = start as u64;
+                        out.push((i, s.collect()));
+                    }
+                    out
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    });
+
+    done.sort_by_key(|(i, _)| *i);
+    done.into_iter().flat_map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// printable runs in several encodings between random bytes
+    fn sample() -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut x: u32 = 1;
+        for i in 0..300 {
+            for _ in 0..(i % 13) {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                data.push((x >> 16) as u8);
+            }
+            match i % 4 {
+                0 => data.extend_from_slice(format!("plain text {} with words", i).as_bytes()),
+                1 => data.extend_from_slice("ünïcödé strîng №".as_bytes()),
+                2 => data.extend(format!("wide {}", i).encode_utf16().flat_map(|u| u.to_le_bytes())),
+                _ => data.extend("big endian".encode_utf16().flat_map(|u| u.to_be_bytes())),
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        let data = sample();
+        for encoding in [Encoding::Ascii, Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+            for (split, max) in [(0, 0), (5, 0), (0, 16)] {
+                let mut config = StringerConfig::default();
+                config.set_encoding(encoding);
+                config.split(split);
+                config.set_window_max_size(max);
+                // many pieces for a small input
+                config.set_piece_size(64);
+
+                let mut s = Stringer::from_slice(&data);
+                s.set_config(config.clone());
+                let sequential: Vec<_> = s.map(|r| (r.offset(), r.end(), r.as_str().to_string())).collect();
+                let parallel: Vec<_> = scan(&data, &config, None, 4)
+                    .into_iter()
+                    .map(|r| (r.offset(), r.end(), r.as_str().to_string()))
+                    .collect();
+                assert!(max != 0 || !sequential.is_empty());
+                assert_eq!(parallel, sequential, "{:?} split {} max {}", encoding, split, max);
+            }
+        }
+    }
+}
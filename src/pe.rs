@@ -0,0 +1,476 @@
+use goblin::pe::header::{Header, SIZEOF_COFF_HEADER, SIZEOF_PE_MAGIC};
+use goblin::pe::section_table::{
+    SectionTable, IMAGE_SCN_CNT_INITIALIZED_DATA, IMAGE_SCN_MEM_EXECUTE,
+};
+
+use crate::config::Encoding;
+use crate::section::Section;
+
+/// Magic number of the DOS header every PE file starts with
+pub const MAGIC: &[u8; 2] = b"MZ";
+/// Name of the strings taken from the string tables of the resources
+pub const STRINGTABLE: &str = "STRINGTABLE";
+/// Name of the strings taken from the version information of the resources
+pub const VERSIONINFO: &str = "VERSIONINFO";
+/// Resource type of string tables
+const RT_STRING: u32 = 6;
+/// Resource type of version information
+const RT_VERSION: u32 = 16;
+/// Depth of the String entries in the version information tree
+const VERSION_DEPTH: usize = 3;
+
+/// reads a little endian 16-bit value at a position of the data
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    let b = data.get(at..at.checked_add(2)?)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+/// reads a little endian 32-bit value at a position of the data
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    let b = data.get(at..at.checked_add(4)?)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// rounds a position up to the next 32-bit boundary
+fn align4(at: usize) -> usize {
+    (at + 3) & !3
+}
+
+/// Returns the sections of a PE file that have contents in the file,
+/// followed by the strings of its resources. The addresses are RVAs,
+/// relative to the image base the file is loaded at.
+/// Sections holding initialized data that are not executable, and the
+/// resource strings, are the data sections.
+pub fn sections(data: &[u8]) -> Result<Vec<Section>, goblin::error::Error> {
+    let header = Header::parse(data)?;
+    // the section table follows the optional header
+    let mut at = header.dos_header.pe_pointer as usize + SIZEOF_PE_MAGIC + SIZEOF_COFF_HEADER +
+        header.coff_header.size_of_optional_header as usize;
+    let table = header.coff_header.sections(data, &mut at)?;
+
+    let mut sections: Vec<Section> = table
+        .iter()
+        .filter_map(|s| {
+            let offset = s.pointer_to_raw_data as usize;
+            // the raw data is padded up to the file alignment
+            let mut size = s.size_of_raw_data as usize;
+            if s.virtual_size != 0 {
+                size = size.min(s.virtual_size as usize);
+            }
+            let size = size.min(data.len().saturating_sub(offset));
+            if size == 0 {
+                return None;
+            }
+
+            Some(Section {
+                name: s.name().unwrap_or_default().to_string(),
+                offset: offset as u64,
+                size: size as u64,
+                address: Some(s.virtual_address as u64),
+                data: s.characteristics & IMAGE_SCN_CNT_INITIALIZED_DATA != 0 &&
+                    s.characteristics & IMAGE_SCN_MEM_EXECUTE == 0,
+                encoding: None,
+                slice: None,
+            })
+        })
+        .collect();
+
+    let directory = header
+        .optional_header
+        .and_then(|h| h.data_directories.get_resource_table().copied());
+    if let Some(d) = directory {
+        let resources = Resources { data, table: &table, root: 0 };
+        if let Some(root) = resources.offset(d.virtual_address) {
+            let resources = Resources { root, ..resources };
+            resources.string_tables(&mut sections);
+            resources.version_info(&mut sections);
+        }
+    }
+    Ok(sections)
+}
+
+/// Resources
+/// Walks the resource directory of a PE file, a tree of
+/// types, names and languages that leads to the resource data.
+struct Resources<'a> {
+    /// the whole file
+    data: &'a [u8],
+    /// sections that map RVAs to positions in the file
+    table: &'a [SectionTable],
+    /// position of the root directory in the file
+    root: usize,
+}
+
+/// A resource found in the directory
+struct Resource {
+    /// RVA of the data
+    rva: u32,
+    /// position of the data in the file
+    offset: usize,
+    /// number of bytes of the data
+    size: usize,
+}
+
+impl Resources<'_> {
+    /// position in the file of an RVA, if it has contents in the file
+    fn offset(&self, rva: u32) -> Option<usize> {
+        let s = self.table.iter().find(|s| {
+            rva >= s.virtual_address && rva - s.virtual_address < s.size_of_raw_data
+        })?;
+        let at = s.pointer_to_raw_data as usize + (rva - s.virtual_address) as usize;
+        (at < self.data.len()).then_some(at)
+    }
+
+    /// entries of the directory at a position relative to the root,
+    /// as their id, the position of what they point to and whether
+    /// that is a directory
+    fn entries(&self, dir: u32) -> Vec<(u32, u32, bool)> {
+        let at = self.root + dir as usize;
+        let (Some(named), Some(ids)) = (u16_at(self.data, at + 12), u16_at(self.data, at + 14)) else {
+            return Vec::new();
+        };
+        (0..named as usize + ids as usize)
+            .filter_map(|i| {
+                let e = at + 16 + i * 8;
+                let id = u32_at(self.data, e)?;
+                let target = u32_at(self.data, e + 4)?;
+                Some((id, target & 0x7fff_ffff, target & 0x8000_0000 != 0))
+            })
+            .collect()
+    }
+
+    /// the resources of a type in every name and language
+    fn of_type(&self, kind: u32) -> Vec<Resource> {
+        let mut found = Vec::new();
+        for (_, names, _) in self.entries(0).into_iter().filter(|e| e.0 == kind && e.2) {
+            for (_, langs, _) in self.entries(names).into_iter().filter(|e| e.2) {
+                for (_, entry, _) in self.entries(langs).into_iter().filter(|e| !e.2) {
+                    let at = self.root + entry as usize;
+                    let (Some(rva), Some(size)) = (u32_at(self.data, at), u32_at(self.data, at + 4)) else {
+                        continue;
+                    };
+                    match self.offset(rva) {
+                        Some(offset) if offset + size as usize <= self.data.len() => {
+                            found.push(Resource { rva, offset, size: size as usize });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// a UTF-16 string inside of a resource
+    fn string(&self, name: &str, r: &Resource, at: usize, size: usize) -> Section {
+        Section {
+            name: name.to_string(),
+            offset: (r.offset + at) as u64,
+            size: size as u64,
+            address: Some(r.rva as u64 + at as u64),
+            data: true,
+            encoding: Some(Encoding::Utf16Le),
+            slice: None,
+        }
+    }
+
+    /// adds the strings of the string tables, every table holds
+    /// 16 strings prefixed with their length in code units
+    fn string_tables(&self, out: &mut Vec<Section>) {
+        for r in self.of_type(RT_STRING) {
+            let mut at = 0;
+            while let Some(n) = u16_at(self.data, r.offset + at).filter(|_| at + 2 <= r.size) {
+                let n = n as usize * 2;
+                at += 2;
+                if n > 0 && at + n <= r.size {
+                    out.push(self.string(STRINGTABLE, &r, at, n));
+                }
+                at += n;
+            }
+        }
+    }
+
+    /// adds the values of the version information, such as the
+    /// product name and the copyright
+    fn version_info(&self, out: &mut Vec<Section>) {
+        for r in self.of_type(RT_VERSION) {
+            self.version_node(&r, 0, r.size, 0, out);
+        }
+    }
+
+    /// Adds the text values of a node of the version information and its
+    /// children. A node is its length, the length of its value, its type,
+    /// a NUL terminated key and the value, followed by the child nodes.
+    fn version_node(&self, r: &Resource, at: usize, end: usize, depth: usize, out: &mut Vec<Section>) {
+        let unit = |i: usize| u16_at(self.data, r.offset + i);
+        let (Some(len), Some(value_len), Some(kind)) = (unit(at), unit(at + 2), unit(at + 4)) else {
+            return;
+        };
+        let end = end.min(at + len as usize);
+
+        let mut key_end = at + 6;
+        while key_end + 2 <= end && unit(key_end) != Some(0) {
+            key_end += 2;
+        }
+        let value = align4(key_end + 2);
+        // text values are measured in code units, binary ones in bytes
+        let value_size = match kind {
+            1 => value_len as usize * 2,
+            _ => value_len as usize,
+        };
+
+        if kind == 1 && value < end {
+            let mut n = value_size.min(end - value);
+            while n >= 2 && unit(value + n - 2) == Some(0) {
+                n -= 2;
+            }
+            if n > 0 {
+                out.push(self.string(VERSIONINFO, r, value, n));
+            }
+        }
+
+        if depth == VERSION_DEPTH {
+            return;
+        }
+        let mut child = align4(value + value_size);
+        while child + 6 <= end {
+            match unit(child) {
+                Some(n) if n > 0 => {
+                    self.version_node(r, child, end, depth + 1, out);
+                    child = align4(child + n as usize);
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StringerConfig;
+    use crate::section::SectionFilter;
+    use crate::Stringer;
+
+    /// position of the resources in the file and their RVA
+    const RSRC: (usize, u32) = (0x400, 0x2000);
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|u| u.to_le_bytes()).collect()
+    }
+
+    fn pad4(v: &mut Vec<u8>) {
+        v.resize(align4(v.len()), 0);
+    }
+
+    /// a string table of 16 strings, the missing ones are empty
+    fn string_table(strings: &[&str]) -> Vec<u8> {
+        let mut v = Vec::new();
+        for i in 0..16 {
+            let s = strings.get(i).copied().unwrap_or_default();
+            v.extend_from_slice(&(s.encode_utf16().count() as u16).to_le_bytes());
+            v.extend_from_slice(&utf16(s));
+        }
+        v
+    }
+
+    /// a node of the version information with a text or binary value
+    fn version_node(key: &str, text: Option<&str>, binary: &[u8], children: &[Vec<u8>]) -> Vec<u8> {
+        let (kind, value_len, value) = match text {
+            Some(t) => (1u16, t.encode_utf16().count() as u16 + 1, utf16(&format!("{}\0", t))),
+            None => (0u16, binary.len() as u16, binary.to_vec()),
+        };
+        let mut v = vec![0, 0];
+        v.extend_from_slice(&value_len.to_le_bytes());
+        v.extend_from_slice(&kind.to_le_bytes());
+        v.extend_from_slice(&utf16(&format!("{}\0", key)));
+        pad4(&mut v);
+        v.extend_from_slice(&value);
+        for c in children {
+            pad4(&mut v);
+            v.extend_from_slice(c);
+        }
+        let len = v.len() as u16;
+        v[..2].copy_from_slice(&len.to_le_bytes());
+        v
+    }
+
+    fn version_info(values: &[(&str, &str)]) -> Vec<u8> {
+        let strings: Vec<Vec<u8>> = values.iter().map(|(k, t)| version_node(k, Some(t), &[], &[])).collect();
+        let table = version_node("040904b0", None, &[], &strings);
+        let file_info = version_node("StringFileInfo", None, &[], &[table]);
+        version_node("VS_VERSION_INFO", None, &[0xbd, 0x04, 0xef, 0xfe], &[file_info])
+    }
+
+    /// a resource directory with a single name and language for each
+    /// resource, the data follows the directory
+    fn resources(types: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let dir = |entries: &[(u32, u32)]| {
+            let mut v = vec![0; 16];
+            v[14..16].copy_from_slice(&(entries.len() as u16).to_le_bytes());
+            for (id, target) in entries {
+                v.extend_from_slice(&id.to_le_bytes());
+                v.extend_from_slice(&target.to_le_bytes());
+            }
+            v
+        };
+        // root, then a name, a language directory and a data entry per type
+        let root = 16 + 8 * types.len() as u32;
+        let node = |i: usize| root + i as u32 * 56;
+        let mut data = node(types.len());
+        let mut v = dir(&types.iter().enumerate().map(|(i, t)| (t.0, node(i) | 0x8000_0000)).collect::<Vec<_>>());
+        let mut blobs = Vec::new();
+        for (i, (_, blob)) in types.iter().enumerate() {
+            v.extend_from_slice(&dir(&[(1, (node(i) + 24) | 0x8000_0000)]));
+            v.extend_from_slice(&dir(&[(0x409, node(i) + 48)]));
+            v.extend_from_slice(&(RSRC.1 + data).to_le_bytes());
+            v.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+            blobs.extend_from_slice(blob);
+            pad4(&mut blobs);
+            data += align4(blob.len()) as u32;
+        }
+        v.extend_from_slice(&blobs);
+        v
+    }
+
+    /// a PE32+ file with a .text, a .rdata and a .rsrc section
+    fn pe_of(rsrc: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; 0x200];
+        data[..2].copy_from_slice(MAGIC);
+        data[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        data[0x80..0x84].copy_from_slice(b"PE\0\0");
+        // the file header and the optional header with 16 data directories
+        let coff = 0x84;
+        data[coff..coff + 2].copy_from_slice(&0x8664u16.to_le_bytes());
+        data[coff + 2..coff + 4].copy_from_slice(&3u16.to_le_bytes());
+        data[coff + 16..coff + 18].copy_from_slice(&240u16.to_le_bytes());
+        data[coff + 18..coff + 20].copy_from_slice(&0x22u16.to_le_bytes());
+        let opt = coff + 20;
+        data[opt..opt + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+        data[opt + 32..opt + 36].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[opt + 36..opt + 40].copy_from_slice(&0x200u32.to_le_bytes());
+        data[opt + 56..opt + 60].copy_from_slice(&0x3000u32.to_le_bytes());
+        data[opt + 60..opt + 64].copy_from_slice(&0x200u32.to_le_bytes());
+        data[opt + 108..opt + 112].copy_from_slice(&16u32.to_le_bytes());
+        data[opt + 128..opt + 132].copy_from_slice(&RSRC.1.to_le_bytes());
+        data[opt + 132..opt + 136].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+
+        let sections: [(&[u8], u32, usize, u32, u32); 3] = [
+            (b".text", 0x0800, 0x200, 0x60000020, 0x200),
+            (b".rdata", 0x1000, 0x300, 0x40000040, 0x100),
+            (b".rsrc", RSRC.1, RSRC.0, 0x40000040, align4(rsrc.len()) as u32),
+        ];
+        let mut at = opt + 240;
+        for (name, rva, offset, flags, size) in sections {
+            data[at..at + name.len()].copy_from_slice(name);
+            data[at + 8..at + 12].copy_from_slice(&size.to_le_bytes());
+            data[at + 12..at + 16].copy_from_slice(&rva.to_le_bytes());
+            data[at + 16..at + 20].copy_from_slice(&size.to_le_bytes());
+            data[at + 20..at + 24].copy_from_slice(&(offset as u32).to_le_bytes());
+            data[at + 36..at + 40].copy_from_slice(&flags.to_le_bytes());
+            at += 40;
+        }
+
+        let mut text = b"\x55\x48code string\xc3".to_vec();
+        text.resize(0x100, 0xcc);
+        data.extend_from_slice(&text);
+        let mut rdata = b"\0read only string\0".to_vec();
+        rdata.resize(0x100, 0);
+        data.extend_from_slice(&rdata);
+        data.extend_from_slice(rsrc);
+        pad4(&mut data);
+        data
+    }
+
+    fn sample() -> Vec<u8> {
+        pe_of(&resources(&[
+            (RT_STRING, string_table(&["", "Hello from the table", "Second entry"])),
+            (RT_VERSION, version_info(&[("CompanyName", "Widget Corp"), ("ProductName", "Widget Maker")])),
+        ]))
+    }
+
+    /// the resource strings of the sections of a PE file
+    fn strings(data: &[u8]) -> Vec<(String, String)> {
+        sections(data)
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.encoding.is_some())
+            .map(|s| {
+                let bytes = &data[s.offset as usize..(s.offset + s.size) as usize];
+                (s.name, String::from_utf8(Encoding::Utf16Le.to_utf8(bytes)).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resource_strings() {
+        let data = sample();
+        let found = sections(&data).unwrap();
+        let names: Vec<(&str, bool)> = found.iter().take(3).map(|s| (s.name.as_str(), s.data)).collect();
+        assert_eq!(names, vec![(".text", false), (".rdata", true), (".rsrc", true)]);
+        assert_eq!(strings(&data), vec![
+            (STRINGTABLE.to_string(), "Hello from the table".to_string()),
+            (STRINGTABLE.to_string(), "Second entry".to_string()),
+            (VERSIONINFO.to_string(), "Widget Corp".to_string()),
+            (VERSIONINFO.to_string(), "Widget Maker".to_string()),
+        ]);
+
+        // the addresses are the RVAs of the strings
+        for s in found.iter().filter(|s| s.encoding.is_some()) {
+            assert_eq!(s.address, Some(s.offset - RSRC.0 as u64 + RSRC.1 as u64));
+        }
+    }
+
+    #[test]
+    fn malformed_resources() {
+        let table = string_table(&["Hello from the table"]);
+        // the data entry points past the end of the file
+        let mut past = resources(&[(RT_STRING, table.clone())]);
+        past[72..76].copy_from_slice(&(RSRC.1 + 0x1000).to_le_bytes());
+        // the directory claims more entries than the resources hold
+        let mut entries = resources(&[(RT_STRING, table.clone())]);
+        entries[12..14].copy_from_slice(&0xffffu16.to_le_bytes());
+        // a string claims more code units than the table holds
+        let mut long = table.clone();
+        long[..2].copy_from_slice(&0x7fffu16.to_le_bytes());
+        // a version node is longer than the resource and its child is empty
+        let mut node = version_info(&[("ProductName", "Widget Maker")]);
+        node[..2].copy_from_slice(&0xffffu16.to_le_bytes());
+        let empty = version_node("VS_VERSION_INFO", None, &[], &[vec![0; 8]]);
+
+        assert_eq!(strings(&pe_of(&past)), Vec::new());
+        assert_eq!(strings(&pe_of(&entries)), vec![(STRINGTABLE.to_string(), "Hello from the table".to_string())]);
+        assert_eq!(strings(&pe_of(&resources(&[(RT_STRING, long)]))), Vec::new());
+        assert_eq!(strings(&pe_of(&resources(&[(RT_VERSION, node)]))), vec![
+            (VERSIONINFO.to_string(), "Widget Maker".to_string()),
+        ]);
+        assert_eq!(strings(&pe_of(&resources(&[(RT_VERSION, empty)]))), Vec::new());
+
+        // truncated files keep the sections and resources that are whole
+        let data = sample();
+        for len in 0..data.len() {
+            let Ok(found) = sections(&data[..len]) else {
+                continue;
+            };
+            assert!(found.iter().all(|s| s.offset + s.size <= len as u64), "truncated at {}", len);
+        }
+    }
+
+    #[test]
+    fn strings_in_resources_once() {
+        let data = sample();
+        let mut config = StringerConfig::default();
+        config.set_encoding(Encoding::Utf16Le);
+        config.set_sections(SectionFilter::Data);
+        let mut s = Stringer::from_slice(&data);
+        s.set_config(config);
+        s.read_strings_sections().unwrap();
+        let found: Vec<(&str, Option<&str>)> = s.results.iter().map(|r| (r.as_str(), r.section())).collect();
+        for string in ["Hello from the table", "Second entry", "Widget Corp", "Widget Maker"] {
+            let sections: Vec<Option<&str>> = found.iter().filter(|r| r.0 == string).map(|r| r.1).collect();
+            assert!(sections == [Some(STRINGTABLE)] || sections == [Some(VERSIONINFO)], "{}: {:?}", string, sections);
+        }
+        // the other strings of the resources are still found in the section
+        assert!(found.contains(&("ProductName", Some(".rsrc"))));
+    }
+}
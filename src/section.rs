@@ -0,0 +1,156 @@
+use crate::config::Encoding;
+use crate::{elf, macho, pe};
+
+/// Number of bytes needed to tell an object file from its magic number,
+/// the header of a universal binary is the longest
+pub const MAGIC_LEN: usize = 8;
+
+/// Which parts of an object file are scanned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectionFilter {
+    /// the whole input, like any other file
+    All,
+    /// the sections holding initialized data, like `strings -d`
+    Data,
+    /// the sections with one of the names
+    Names(Vec<String>),
+}
+
+/// A section of an object file that is scanned on its own
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// name of the section, such as `.rodata` or `__TEXT,__cstring`,
+    /// empty when the section is a whole slice of a universal binary
+    pub name: String,
+    /// position of the first byte of the section in the file
+    pub offset: u64,
+    /// number of bytes the section takes in the file
+    pub size: u64,
+    /// virtual address of the section if it is loaded in memory
+    pub address: Option<u64>,
+    /// the section holds data instead of code or metadata
+    pub data: bool,
+    /// the section is a single string in this encoding, such as a
+    /// resource string, which is taken whole instead of being scanned
+    pub encoding: Option<Encoding>,
+    /// architecture of the slice of a universal binary the section is in
+    pub slice: Option<String>,
+}
+
+impl SectionFilter {
+    /// checks if a section is scanned under the filter. Mach-O sections
+    /// are also selected by the name of their segment or their own name.
+    pub fn selects(&self, s: &Section) -> bool {
+        match self {
+            SectionFilter::All => true,
+            SectionFilter::Data => s.data,
+            SectionFilter::Names(n) => {
+                n.contains(&s.name) || s.name.split(',').any(|p| n.iter().any(|n| n == p))
+            }
+        }
+    }
+}
+
+/// Joins section names that were split on commas back into the
+/// `SEGMENT,section` names of Mach-O, so `__TEXT,__cstring,.rodata`
+/// is read as `__TEXT,__cstring` and `.rodata`. A segment name is
+/// upper case while a section name is not.
+pub fn join_names(names: &[String]) -> Vec<String> {
+    let segment = |n: &str| n.starts_with("__") && !n.bytes().any(|b| b.is_ascii_lowercase());
+    let section = |n: &str| n.starts_with("__") && n.bytes().any(|b| b.is_ascii_lowercase());
+
+    let mut joined: Vec<String> = Vec::new();
+    for n in names {
+        match joined.last_mut() {
+            Some(last) if segment(last) && section(n) => {
+                last.push(',');
+                last.push_str(n);
+            }
+            _ => joined.push(n.clone()),
+        }
+    }
+    joined
+}
+
+/// Checks if the first bytes of an input are those of an object file
+/// whose sections can be selected by the filter, so an input that is
+/// not is never read into memory to be parsed.
+pub fn is_object(head: &[u8], filter: &SectionFilter) -> bool {
+    macho::is_macho(head) || (*filter != SectionFilter::All &&
+        (head.starts_with(elf::MAGIC) || head.starts_with(pe::MAGIC)))
+}
+
+/// Returns the sections of an object file that the filter selects in
+/// file order. Returns None when the whole input should be scanned,
+/// because every section is selected or because the input is not an
+/// object file that can be parsed, which is what `strings -d` does.
+/// Universal binaries are limited to the slices of the architecture,
+/// and scanned slice by slice when every section is selected.
+pub fn select(data: &[u8], filter: &SectionFilter, arch: Option<&str>) -> Option<Vec<Section>> {
+    let all = *filter == SectionFilter::All;
+    if all && arch.is_none() {
+        return None;
+    }
+
+    let sections = if macho::is_macho(data) {
+        macho::sections(data, arch, all).ok()?
+    } else if all {
+        return None;
+    } else if data.starts_with(elf::MAGIC) {
+        elf::sections(data).ok()?
+    } else if data.starts_with(pe::MAGIC) {
+        pe::sections(data).ok()?
+    } else {
+        return None;
+    };
+    if all {
+        return Some(sections);
+    }
+
+    let mut selected: Vec<Section> = sections.into_iter().filter(|s| filter.selects(s)).collect();
+    selected.sort_by_key(|s| s.offset);
+    Some(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(n: &[&str]) -> Vec<String> {
+        n.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn joined_names() {
+        assert_eq!(join_names(&names(&["__TEXT", "__cstring", ".rodata"])), names(&["__TEXT,__cstring", ".rodata"]));
+        assert_eq!(join_names(&names(&["__DATA", "__DATA", "__const"])), names(&["__DATA", "__DATA,__const"]));
+        assert_eq!(
+            join_names(&names(&["__TEXT", "__cstring", "__const", "__TEXT"])),
+            names(&["__TEXT,__cstring", "__const", "__TEXT"])
+        );
+        // section names only follow segment names
+        assert_eq!(join_names(&names(&[".data", "__cstring"])), names(&[".data", "__cstring"]));
+        assert_eq!(join_names(&names(&["STRINGTABLE", "VERSIONINFO"])), names(&["STRINGTABLE", "VERSIONINFO"]));
+    }
+
+    #[test]
+    fn selected_names() {
+        let section = |name: &str| Section {
+            name: name.to_string(),
+            offset: 0,
+            size: 1,
+            address: None,
+            data: false,
+            encoding: None,
+            slice: None,
+        };
+        let filter = SectionFilter::Names(names(&["__TEXT", "__data", ".rodata"]));
+        assert!(filter.selects(&section("__TEXT,__cstring")));
+        assert!(filter.selects(&section("__DATA,__data")));
+        assert!(filter.selects(&section(".rodata")));
+        assert!(!filter.selects(&section("__DATA,__const")));
+        assert!(!filter.selects(&section(".rodata.str")));
+        assert!(!SectionFilter::Data.selects(&section(".text")));
+        assert!(SectionFilter::All.selects(&section(".text")));
+    }
+}
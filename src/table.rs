@@ -0,0 +1,77 @@
+/// Class of a byte that is not printable on its own
+pub const SKIP: u8 = 0;
+/// Class of a byte that is a printable character on its own
+pub const PRINT: u8 = 1;
+/// Class of a byte that needs to be decoded with the bytes after it
+pub const DECODE: u8 = 2;
+
+/// ByteTable
+/// A lookup table with the class of every byte value under a
+/// configuration. It is built once from the configuration so the
+/// scanner does a single load per byte instead of the checks of
+/// `should_read`, and whole runs of a class are measured at once.
+#[derive(Clone)]
+pub struct ByteTable {
+    /// class of each byte value
+    class: [u8; 256],
+}
+
+impl ByteTable {
+    /// builds the table from the class of every byte value
+    pub fn from_fn<F: Fn(u8) -> u8>(f: F) -> Self {
+        let mut class = [SKIP; 256];
+        for (b, c) in class.iter_mut().enumerate() {
+            *c = f(b as u8);
+        }
+        ByteTable { class }
+    }
+
+    /// class of a single byte
+    pub fn class(&self, b: u8) -> u8 {
+        self.class[b as usize]
+    }
+
+    /// Returns how many bytes at the start of the data are of the given
+    /// class. The bytes are looked up one at a time, checking words or
+    /// blocks of bytes at once was not faster on the runs of binaries.
+    pub fn span(&self, data: &[u8], class: u8) -> usize {
+        data.iter().take_while(|&&b| self.class[b as usize] == class).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_of_every_class() {
+        let mut x: u32 = 7;
+        let data: Vec<u8> = (0..4096)
+            .map(|i| {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                // long runs of text between random bytes
+                match (i / 64) % 2 {
+                    0 => b'a' + (x >> 16) as u8 % 26,
+                    _ => (x >> 16) as u8,
+                }
+            })
+            .collect();
+        let classes = [
+            |b: u8| if b.is_ascii_alphanumeric() { PRINT } else { SKIP },
+            |b: u8| match b {
+                0x20..=0x7e => PRINT,
+                0x80.. => DECODE,
+                _ => SKIP,
+            },
+        ];
+        for f in classes {
+            let table = ByteTable::from_fn(f);
+            for i in 0..data.len() {
+                for class in [SKIP, PRINT, DECODE] {
+                    let run = data[i..].iter().take_while(|&&b| f(b) == class).count();
+                    assert_eq!(table.span(&data[i..], class), run);
+                }
+            }
+        }
+    }
+}
@@ -1,23 +1,392 @@
-pub fn write<W: ?Sized>(
+use crate::config::OutputFormat;
+use crate::error::StringerError;
+
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+
+/// writes all the results in the given format
+pub fn write<W>(
     w: &mut W,
     buffer: &Vec<crate::StringerResult>,
-) -> Result<(), crate::error::StringerError>
+    format: OutputFormat,
+) -> Result<(), StringerError>
 where
-    W: std::io::Write
+    W: std::io::Write + ?Sized
 {
+    let mut writer = Writer::new(w, format);
     for r in buffer {
-        match w.write(serde_json::to_string(r).unwrap().as_bytes()) {
-            Err(_) => {
-                println!("unable to write to buffer");
-                return Err(crate::error::StringerError::new(String::from(
-                    "unable to write to buffer",
-                )));
+        writer.write(r)?;
+    }
+
+    writer.finish()
+}
+
+/// where the formatted results end up
+enum Sink<W: std::io::Write> {
+    /// formats that are written line by line
+    Plain(W),
+    /// a single xml document
+    Xml(quick_xml::Writer<W>),
+    /// delimiter separated records with a header row
+    Csv(Box<csv::Writer<W>>),
+}
+
+/// columns of the CSV and TSV formats
+const COLUMNS: [&str; 14] = [
+    "source", "compression", "offset", "end", "slice", "section", "address", "length",
+    "entropy", "kinds", "findings", "patterns", "encoding", "string",
+];
+
+/// Writer
+/// Writes results one by one in an output format, so results
+/// can be streamed out while the input is still being read.
+/// `finish` must be called once all the results are written
+/// for formats that need to close the document.
+pub struct Writer<W: std::io::Write> {
+    /// the output and its format specific state
+    sink: Sink<W>,
+    /// format the results are written in
+    format: OutputFormat,
+    /// the header of the document is written
+    started: bool,
+    /// number of written results flagged by the secret rules
+    findings: u64,
+}
+
+/// XML 1.0 cannot carry most control characters even when escaped,
+/// those are replaced so the document stays well formed
+fn xml_text(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\t' | '\n' | '\r' => c,
+            c if (c as u32) < 0x20 => char::REPLACEMENT_CHARACTER,
+            c => c,
+        })
+        .collect()
+}
+
+/// the compressions of a result joined outermost first, such as `xz,gzip`
+fn compression(r: &crate::StringerResult) -> String {
+    r.compression.iter().map(|c| c.name()).collect::<Vec<_>>().join(",")
+}
+
+/// the kinds of values found in a result joined, such as `url,domain`
+fn kinds(r: &crate::StringerResult) -> String {
+    r.kinds.iter().map(|k| k.name()).collect::<Vec<_>>().join(",")
+}
+
+/// the findings of a result joined, such as `jwt:medium`
+fn findings(r: &crate::StringerResult) -> String {
+    r.findings.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// the ids of the patterns that matched a result joined, such as `0,2`
+fn patterns(r: &crate::StringerResult) -> String {
+    r.patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+}
+
+impl<W: std::io::Write> Writer<W> {
+    /// creates a writer of the given format on top of an output
+    pub fn new(w: W, format: OutputFormat) -> Self {
+        let sink = match format {
+            OutputFormat::XML => Sink::Xml(quick_xml::Writer::new_with_indent(w, b' ', 2)),
+            // fields with the delimiter, quotes or line breaks are quoted
+            OutputFormat::CSV => Sink::Csv(Box::new(csv::Writer::from_writer(w))),
+            OutputFormat::TSV => Sink::Csv(Box::new(
+                csv::WriterBuilder::new().delimiter(b'\t').from_writer(w),
+            )),
+            OutputFormat::JSON | OutputFormat::YAML |
+            OutputFormat::Literal => Sink::Plain(w),
+        };
+
+        Writer {
+            sink,
+            format,
+            started: false,
+            findings: 0,
+        }
+    }
+
+    /// writes the opening of the document if it is not written yet
+    fn start(&mut self) -> Result<(), StringerError> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+
+        match self.sink {
+            Sink::Xml(ref mut x) => {
+                x.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+                    .map_err(StringerError::output)?;
+                x.write_event(Event::Start(BytesStart::new("strings")))
+                    .map_err(StringerError::output)?;
+            }
+            Sink::Csv(ref mut c) => {
+                c.write_record(COLUMNS).map_err(StringerError::output)?;
+            }
+            Sink::Plain(_) => {}
+        }
+        Ok(())
+    }
+
+    /// writes a single result
+    pub fn write(&mut self, r: &crate::StringerResult) -> Result<(), StringerError> {
+        self.start()?;
+        if !r.findings.is_empty() {
+            self.findings += 1;
+        }
+
+        match self.sink {
+            Sink::Plain(ref mut w) => {
+                match self.format {
+                    OutputFormat::JSON => {
+                        serde_json::to_writer(&mut *w, r).map_err(StringerError::output)?;
+                        w.write_all(b"\n").map_err(StringerError::output)
+                    }
+                    // every result is an item of a single top level sequence
+                    OutputFormat::YAML => {
+                        let y = serde_yaml::to_string(std::slice::from_ref(r))
+                            .map_err(StringerError::output)?;
+                        w.write_all(y.as_bytes()).map_err(StringerError::output)
+                    }
+                    _ => writeln!(w, "{}", r).map_err(StringerError::output),
+                }
+            }
+            Sink::Xml(ref mut x) => {
+                let offset = r.offset.to_string();
+                let end = r.end.to_string();
+                let address = r.address.map(|a| a.to_string());
+                let compression = compression(r);
+                let length = r.length.map(|n| n.to_string());
+                let entropy = r.entropy.map(|h| format!("{:.2}", h));
+                let kinds = kinds(r);
+                let findings = findings(r);
+                let patterns = patterns(r);
+
+                let mut el = x.create_element("string");
+                if let Some(ref s) = r.source {
+                    el = el.with_attribute(("source", s.as_str()));
+                }
+                if !compression.is_empty() {
+                    el = el.with_attribute(("compression", compression.as_str()));
+                }
+                el = el.with_attribute(("offset", offset.as_str()))
+                    .with_attribute(("end", end.as_str()));
+                if let Some(ref s) = r.slice {
+                    el = el.with_attribute(("slice", s.as_str()));
+                }
+                if let Some(ref s) = r.section {
+                    el = el.with_attribute(("section", s.as_str()));
+                }
+                if let Some(ref a) = address {
+                    el = el.with_attribute(("address", a.as_str()));
+                }
+                if let Some(ref n) = length {
+                    el = el.with_attribute(("length", n.as_str()));
+                }
+                if let Some(ref h) = entropy {
+                    el = el.with_attribute(("entropy", h.as_str()));
+                }
+                if !kinds.is_empty() {
+                    el = el.with_attribute(("kinds", kinds.as_str()));
+                }
+                if !findings.is_empty() {
+                    el = el.with_attribute(("findings", findings.as_str()));
+                }
+                if !patterns.is_empty() {
+                    el = el.with_attribute(("patterns", patterns.as_str()));
+                }
+                el.with_attribute(("encoding", r.encoding_name()))
+                    .write_text_content(BytesText::new(&xml_text(r.string.to_str().unwrap())))
+                    .map(|_| ())
+                    .map_err(StringerError::output)
             }
-            _ => {
+            Sink::Csv(ref mut c) => {
+                c.write_record([
+                    r.source.clone().unwrap_or_default(),
+                    compression(r),
+                    r.offset.to_string(),
+                    r.end.to_string(),
+                    r.slice.clone().unwrap_or_default(),
+                    r.section.clone().unwrap_or_default(),
+                    r.address.map(|a| a.to_string()).unwrap_or_default(),
+                    r.length.map(|n| n.to_string()).unwrap_or_default(),
+                    r.entropy.map(|h| format!("{:.2}", h)).unwrap_or_default(),
+                    kinds(r),
+                    findings(r),
+                    patterns(r),
+                    r.encoding_name().to_string(),
+                    r.string.to_str().unwrap().to_string(),
+                ])
+                .map_err(StringerError::output)
             }
         }
-        w.write_fmt(format_args!("\n")).unwrap();
     }
 
-    Ok(())
+    /// number of written results that are flagged by the secret rules
+    pub fn findings(&self) -> u64 {
+        self.findings
+    }
+
+    /// closes the document and flushes the output
+    pub fn finish(&mut self) -> Result<(), StringerError> {
+        // an empty yaml stream would be null instead of a sequence
+        if !self.started && self.format == OutputFormat::YAML {
+            if let Sink::Plain(ref mut w) = self.sink {
+                w.write_all(b"[]\n").map_err(StringerError::output)?;
+            }
+        }
+        self.start()?;
+
+        let w = match self.sink {
+            Sink::Plain(ref mut w) => w,
+            Sink::Csv(ref mut c) => {
+                return c.flush().map_err(StringerError::output);
+            }
+            Sink::Xml(ref mut x) => {
+                x.write_event(Event::End(BytesEnd::new("strings")))
+                    .map_err(StringerError::output)?;
+                x.get_mut().write_all(b"\n").map_err(StringerError::output)?;
+                x.get_mut()
+            }
+        };
+        w.flush().map_err(StringerError::output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StringerResult;
+
+    /// strings with the characters each format must quote or escape
+    const STRINGS: [&str; 5] = [
+        "plain",
+        "a,b \"quoted\" 'single'",
+        "tab\there; semi",
+        "two\nlines\r\nand <tags> & \u{1}control\u{7f}",
+        "ünïcödé",
+    ];
+
+    fn results() -> Vec<StringerResult> {
+        STRINGS
+            .iter()
+            .enumerate()
+            .map(|(i, s)| StringerResult {
+                string: std::ffi::CString::new(*s).unwrap(),
+                length: Some(s.chars().count() as u64),
+                entropy: None,
+                kinds: Vec::new(),
+                findings: Vec::new(),
+                patterns: vec![0, i],
+                encoding: crate::config::Encoding::Utf8,
+                raw: false,
+                offset: i as u64 * 100,
+                end: i as u64 * 100 + s.len() as u64,
+                radix: None,
+                source: Some("dir/a,b.bin".to_string()),
+                section: None,
+                address: None,
+                slice: None,
+                compression: vec![crate::decompress::Compression::Gzip],
+            })
+            .collect()
+    }
+
+    fn output(results: &Vec<StringerResult>, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write(&mut out, results, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn csv_and_tsv() {
+        for (format, delimiter) in [(OutputFormat::CSV, b','), (OutputFormat::TSV, b'\t')] {
+            let out = output(&results(), format);
+            let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(out.as_bytes());
+            assert_eq!(reader.headers().unwrap(), &COLUMNS[..]);
+            let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+            assert_eq!(records.len(), STRINGS.len());
+            for (i, (r, s)) in records.iter().zip(STRINGS).enumerate() {
+                assert_eq!(r.len(), COLUMNS.len());
+                assert_eq!(&r[0], "dir/a,b.bin");
+                assert_eq!(&r[1], "gzip");
+                assert_eq!(&r[2], (i * 100).to_string());
+                assert_eq!(&r[11], format!("0,{}", i));
+                assert_eq!(&r[12], "utf-8");
+                assert_eq!(&r[13], s);
+            }
+
+            // only the header without results
+            assert_eq!(output(&Vec::new(), format).lines().count(), 1);
+        }
+    }
+
+    #[test]
+    fn json_lines() {
+        let out = output(&results(), OutputFormat::JSON);
+        let lines: Vec<serde_json::Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), STRINGS.len());
+        for (v, s) in lines.iter().zip(STRINGS) {
+            assert_eq!(v["string"], s);
+            assert_eq!(v["source"], "dir/a,b.bin");
+            assert_eq!(v["compression"], serde_json::json!(["gzip"]));
+            assert!(v.get("address").is_none());
+        }
+        assert_eq!(output(&Vec::new(), OutputFormat::JSON), "");
+    }
+
+    #[test]
+    fn yaml_sequence() {
+        let out = output(&results(), OutputFormat::YAML);
+        let items: Vec<serde_yaml::Value> = serde_yaml::from_str(&out).unwrap();
+        assert_eq!(items.len(), STRINGS.len());
+        for (v, s) in items.iter().zip(STRINGS) {
+            assert_eq!(v["string"].as_str(), Some(s));
+            assert_eq!(v["encoding"].as_str(), Some("utf-8"));
+        }
+
+        // an empty stream is an empty sequence rather than null
+        let out = output(&Vec::new(), OutputFormat::YAML);
+        assert_eq!(out, "[]\n");
+        let items: Vec<serde_yaml::Value> = serde_yaml::from_str(&out).unwrap();
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn xml_document() {
+        let out = output(&results(), OutputFormat::XML);
+        let mut reader = quick_xml::Reader::from_str(&out);
+        let (mut strings, mut offsets) = (Vec::new(), Vec::new());
+        let mut text = None;
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(e) if e.name().as_ref() == b"string" => {
+                    let offset = e.try_get_attribute("offset").unwrap().unwrap();
+                    offsets.push(offset.unescape_value().unwrap().into_owned());
+                    text = Some(String::new());
+                }
+                Event::Text(t) => {
+                    if let Some(ref mut s) = text {
+                        s.push_str(&t.unescape().unwrap());
+                    }
+                }
+                Event::End(e) if e.name().as_ref() == b"string" => strings.extend(text.take()),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        // control characters other than line breaks and tabs cannot be in XML 1.0
+        let expected: Vec<String> = STRINGS.iter().map(|s| s.replace('\u{1}', "\u{fffd}")).collect();
+        assert_eq!(strings, expected);
+        assert_eq!(offsets, ["0", "100", "200", "300", "400"]);
+
+        let out = output(&Vec::new(), OutputFormat::XML);
+        assert!(out.contains("<strings>") && out.trim_end().ends_with("</strings>"));
+    }
+
+    #[test]
+    fn literal_lines() {
+        let out = output(&results()[..1].to_vec(), OutputFormat::Literal);
+        assert_eq!(out, "dir/a,b.bin: 5, {0,0} plain\n");
+    }
 }